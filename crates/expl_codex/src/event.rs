@@ -0,0 +1,63 @@
+use super::{Codex, Id};
+use bevy_asset::AssetId;
+use bevy_ecs::prelude::*;
+use bevy_reflect::TypePath;
+
+/// Sent when a codex asset has been reloaded, listing the entries that changed.
+#[derive(Event, Debug)]
+pub struct CodexChanged<Entry>
+where
+    Entry: TypePath + Send + Sync,
+{
+    pub codex: AssetId<Codex<Entry>>,
+    pub added: Vec<Id<Entry>>,
+    pub removed: Vec<Id<Entry>>,
+    pub modified: Vec<Id<Entry>>,
+}
+
+impl<Entry> CodexChanged<Entry>
+where
+    Entry: TypePath + Send + Sync,
+{
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Iterate over all ids that were either added or modified.
+    pub fn updated(&self) -> impl Iterator<Item = &Id<Entry>> {
+        self.added.iter().chain(self.modified.iter())
+    }
+}
+
+impl<Entry> CodexChanged<Entry>
+where
+    Entry: TypePath + Send + Sync + PartialEq,
+{
+    /// Compare two versions of a codex.
+    pub fn between(
+        codex: AssetId<Codex<Entry>>,
+        previous: &Codex<Entry>,
+        current: &Codex<Entry>,
+    ) -> Self {
+        let mut changed = Self {
+            codex,
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+        };
+        for (id, entry) in current.iter() {
+            match previous.get(id) {
+                None => changed.added.push(*id),
+                Some(previous_entry) if previous_entry != entry => changed.modified.push(*id),
+                Some(_) => {}
+            }
+        }
+        changed.removed.extend(
+            previous
+                .iter()
+                .map(|(id, _)| *id)
+                .filter(|id| current.get(id).is_none()),
+        );
+        changed
+    }
+}
@@ -0,0 +1,106 @@
+use toml::{Table, Value};
+
+/// Key of a layer that lists the tags of entries to remove from the layers below.
+pub const REMOVE_KEY: &str = "_remove";
+
+/// Merge the codex definition in `layer` on top of `base`.
+///
+/// Entries in the layer that are not in `base` are added. Entries that already exist are merged
+/// field by field, where nested tables are merged recursively and any other value replaces the
+/// value of the layer below. Entries can be removed by listing their tags under [`REMOVE_KEY`].
+/// ```
+/// use expl_codex::merge_layer;
+///
+/// let mut base: toml::Table = toml::from_str("
+/// [slime]
+/// health = 10
+/// view_radius = 2
+///
+/// [warrior]
+/// health = 10
+/// ").unwrap();
+/// let layer: toml::Table = toml::from_str("
+/// _remove = ['warrior']
+///
+/// [slime]
+/// health = 20
+/// ").unwrap();
+///
+/// merge_layer(&mut base, layer);
+/// assert_eq!(base["slime"]["health"].as_integer(), Some(20));
+/// assert_eq!(base["slime"]["view_radius"].as_integer(), Some(2));
+/// assert!(!base.contains_key("warrior"));
+/// ```
+pub fn merge_layer(base: &mut Table, mut layer: Table) {
+    if let Some(Value::Array(removed)) = layer.remove(REMOVE_KEY) {
+        for tag in removed.iter().filter_map(Value::as_str) {
+            base.remove(tag);
+        }
+    }
+    merge_table(base, layer);
+}
+
+fn merge_table(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(layer_table)) => {
+                merge_table(base_table, layer_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(source: &str) -> Table {
+        toml::from_str(source).unwrap()
+    }
+
+    #[test]
+    fn add_entry() {
+        let mut base = table("[spam]\nvalue = 1");
+        merge_layer(&mut base, table("[egg]\nvalue = 2"));
+
+        assert_eq!(base, table("[spam]\nvalue = 1\n[egg]\nvalue = 2"));
+    }
+
+    #[test]
+    fn override_nested_field() {
+        let mut base = table("[spam]\nvalue = 1\nrange = { low = 0, high = 8 }");
+        merge_layer(&mut base, table("[spam]\nrange = { high = 10 }"));
+
+        assert_eq!(
+            base,
+            table("[spam]\nvalue = 1\nrange = { low = 0, high = 10 }")
+        );
+    }
+
+    #[test]
+    fn replace_array() {
+        let mut base = table("[spam]\nvalue = [1, 2]");
+        merge_layer(&mut base, table("[spam]\nvalue = [3]"));
+
+        assert_eq!(base, table("[spam]\nvalue = [3]"));
+    }
+
+    #[test]
+    fn remove_entry() {
+        let mut base = table("[spam]\nvalue = 1\n[egg]\nvalue = 2");
+        merge_layer(&mut base, table("_remove = ['spam', 'bacon']"));
+
+        assert_eq!(base, table("[egg]\nvalue = 2"));
+    }
+
+    #[test]
+    fn remove_and_redefine_entry() {
+        let mut base = table("[spam]\nvalue = 1\ntext = 'some text'");
+        merge_layer(&mut base, table("_remove = ['spam']\n[spam]\nvalue = 2"));
+
+        assert_eq!(base, table("[spam]\nvalue = 2"));
+    }
+}
@@ -3,16 +3,29 @@ use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize, TypePath};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp,
-    collections::HashMap,
+    collections::{hash_map::Entry as MapEntry, HashMap},
     fmt,
     hash::{Hash, Hasher},
     marker::PhantomData,
     ops::Index,
     sync::Arc,
 };
+use thiserror::Error;
 
+mod event;
+mod layer;
 mod loader;
-pub use loader::{CodexLoader, CodexSource, Error, FromWithLoadContext};
+mod plugin;
+mod registry;
+mod schema;
+pub use event::CodexChanged;
+pub use layer::{merge_layer, REMOVE_KEY};
+pub use loader::{CodexFormat, CodexLoader, CodexSource, Error, FromWithLoadContext};
+pub use plugin::CodexPlugin;
+pub use schema::{
+    codex_json_schema, AssetReference, Diagnostic, Field, Location, Optional, Schema, Validator,
+    Variant,
+};
 
 /// Identifier with a phantom binding to a specific type.
 ///
@@ -47,10 +60,14 @@ pub struct Id<T> {
     _phantom: PhantomData<T>,
 }
 
-// Custom impl to provide compact debug formatting.
+// Custom impl to provide compact debug formatting, showing the tag when it is known.
 impl<T> fmt::Debug for Id<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("Id").field(&self.value).finish()
+        if let Some(tag) = self.tag() {
+            f.debug_tuple("Id").field(&tag).finish()
+        } else {
+            f.debug_tuple("Id").field(&self.value).finish()
+        }
     }
 }
 
@@ -113,6 +130,29 @@ impl<T> Id<T> {
         Self::new(Self::hash_bytes(tag.as_bytes()))
     }
 
+    /// Create identifier from a string tag and record the tag in the global registry.
+    ///
+    /// Ids of entries in a [`Codex`] are registered automatically, this is meant for ids that are
+    /// defined in code only.
+    /// ```
+    /// use expl_codex::Id;
+    ///
+    /// struct Animal;
+    ///
+    /// let id: Id<Animal> = Id::register_tag("horse");
+    /// assert_eq!(format!("{:?}", id), "Id(\"horse\")");
+    /// ```
+    pub fn register_tag(tag: &str) -> Self {
+        let id = Self::from_tag(tag);
+        registry::register(&id, tag);
+        id
+    }
+
+    /// The tag this id was created from if it is known to the registry.
+    pub fn tag(&self) -> Option<Arc<str>> {
+        registry::lookup(self)
+    }
+
     /// A polynomial rolling hash function
     const fn hash_bytes(bytes: &[u8]) -> u64 {
         let len = bytes.len();
@@ -128,13 +168,37 @@ impl<T> Id<T> {
     }
 }
 
+/// Two different tags hashed to the same identifier.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("tag `{tag}` collides with `{existing}`")]
+pub struct TagCollision {
+    pub tag: String,
+    pub existing: String,
+}
+
 /// Defines a codex of data entries identified by [`Id<Entry>`]
-#[derive(Asset, TypePath, Clone, Debug)]
+///
+/// The codex retains the tag of each entry so that ids can be mapped back to a readable name.
+#[derive(Asset, TypePath, Debug)]
 pub struct Codex<Entry>
 where
     Entry: TypePath + Send + Sync,
 {
     lookup: Arc<HashMap<Id<Entry>, Entry>>,
+    tags: Arc<HashMap<Id<Entry>, String>>,
+}
+
+// Custom impl as the entries are shared and don't need to be `Clone`.
+impl<Entry> Clone for Codex<Entry>
+where
+    Entry: TypePath + Send + Sync,
+{
+    fn clone(&self) -> Self {
+        Self {
+            lookup: self.lookup.clone(),
+            tags: self.tags.clone(),
+        }
+    }
 }
 
 /// Used to assemble [Codex] instances.
@@ -143,6 +207,7 @@ where
     Entry: TypePath + Send + Sync,
 {
     lookup: HashMap<Id<Entry>, Entry>,
+    tags: HashMap<Id<Entry>, String>,
 }
 
 impl<Entry> CodexBuilder<Entry>
@@ -152,16 +217,48 @@ where
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             lookup: HashMap::with_capacity(capacity),
+            tags: HashMap::with_capacity(capacity),
         }
     }
 
-    pub fn add(mut self, tag: &str, entry: Entry) -> CodexBuilder<Entry> {
-        self.lookup.insert(Id::from_tag(tag), entry);
-        self
+    /// Add an entry, replacing any previous entry with the same tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tag hashes to the same id as a different tag already in the builder.
+    pub fn add(self, tag: &str, entry: Entry) -> CodexBuilder<Entry> {
+        match self.try_add(tag, entry) {
+            Ok(builder) => builder,
+            Err(collision) => panic!("{}", collision),
+        }
+    }
+
+    /// Add an entry, replacing any previous entry with the same tag.
+    ///
+    /// Fails if the tag hashes to the same id as a different tag already in the builder.
+    pub fn try_add(mut self, tag: &str, entry: Entry) -> Result<CodexBuilder<Entry>, TagCollision> {
+        let id = Id::from_tag(tag);
+        match self.tags.entry(id) {
+            MapEntry::Occupied(existing) if existing.get() != tag => {
+                return Err(TagCollision {
+                    tag: tag.to_string(),
+                    existing: existing.get().clone(),
+                });
+            }
+            MapEntry::Occupied(_) => {}
+            MapEntry::Vacant(vacant) => {
+                vacant.insert(tag.to_string());
+            }
+        }
+        self.lookup.insert(id, entry);
+        Ok(self)
     }
 
     pub fn build(self) -> Codex<Entry> {
-        Codex::new(self.lookup)
+        for (id, tag) in &self.tags {
+            registry::register(id, tag);
+        }
+        Codex::from_parts(self.lookup, self.tags)
     }
 }
 
@@ -169,9 +266,22 @@ impl<Entry> Codex<Entry>
 where
     Entry: TypePath + Send + Sync,
 {
+    /// Create a codex from entries keyed by id.
+    ///
+    /// The tags are taken from the registry, so entries whose id was not created with
+    /// [`Id::register_tag`] or by a [`CodexBuilder`] can only be found by id.
     pub fn new(lookup: HashMap<Id<Entry>, Entry>) -> Self {
+        let tags = lookup
+            .keys()
+            .filter_map(|id| Some((*id, id.tag()?.to_string())))
+            .collect();
+        Self::from_parts(lookup, tags)
+    }
+
+    fn from_parts(lookup: HashMap<Id<Entry>, Entry>, tags: HashMap<Id<Entry>, String>) -> Self {
         Self {
             lookup: Arc::new(lookup),
+            tags: Arc::new(tags),
         }
     }
 
@@ -179,9 +289,35 @@ where
         CodexBuilder::with_capacity(capacity)
     }
 
+    pub fn get(&self, id: &Id<Entry>) -> Option<&Entry> {
+        self.lookup.get(id)
+    }
+
+    /// Lookup an entry by the tag it was defined with.
+    pub fn get_by_tag(&self, tag: &str) -> Option<&Entry> {
+        let id = Id::from_tag(tag);
+        self.tags
+            .get(&id)
+            .filter(|existing| *existing == tag)
+            .and_then(|_| self.lookup.get(&id))
+    }
+
+    /// The tag of the entry identified by `id`.
+    pub fn tag_of(&self, id: &Id<Entry>) -> Option<&str> {
+        self.tags.get(id).map(String::as_str)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&Id<Entry>, &Entry)> {
         self.lookup.iter()
     }
+
+    /// Iterate over entries together with their tags, which are empty for entries without a known
+    /// tag.
+    pub fn iter_with_tags(&self) -> impl Iterator<Item = (&Id<Entry>, &str, &Entry)> {
+        self.lookup
+            .iter()
+            .map(|(id, entry)| (id, self.tags.get(id).map_or("", String::as_str), entry))
+    }
 }
 
 impl<Entry> Index<&Id<Entry>> for Codex<Entry>
@@ -222,9 +358,9 @@ mod tests {
             memory::{Dir, MemoryAssetReader},
             AssetSource, AssetSourceId,
         },
-        AssetApp, AssetPlugin, AssetServer, Assets, Handle, LoadContext,
+        AssetApp, AssetId, AssetPlugin, AssetServer, Assets, Handle, LoadContext,
     };
-    use bevy_ecs::component::Component;
+    use bevy_ecs::{component::Component, event::Events};
     use std::path::Path;
 
     #[derive(Debug, TypePath, Deserialize)]
@@ -233,7 +369,7 @@ mod tests {
         text: String,
     }
 
-    #[derive(Debug, TypePath)]
+    #[derive(Debug, TypePath, PartialEq)]
     struct MenuItem {
         source: String,
         value: u32,
@@ -255,7 +391,7 @@ mod tests {
     }
 
     impl CodexSource for MenuItem {
-        const EXTENSION: &'static str = "menu.toml";
+        const EXTENSION: &'static str = "menu";
     }
 
     impl FromWithLoadContext<RawMenuItem> for MenuItem {
@@ -311,10 +447,110 @@ text = 'some other text'
     }
 
     #[test]
-    fn codex_loader() {
-        let root = Dir::default();
-        root.insert_asset_text(Path::new("some.menu.toml"), CODEXFILE);
+    fn codex_tags() {
+        let codex: Codex<MenuItem> = Codex::builder_with_capacity(0)
+            .add("spam", MenuItem::new(10))
+            .add("bacon", MenuItem::new(11))
+            .build();
+
+        assert_eq!(codex.tag_of(&SPAM), Some("spam"));
+        assert_eq!(codex.tag_of(&Id::from_tag("egg")), None);
+        assert_eq!(codex.get_by_tag("bacon").map(|entry| entry.value), Some(11));
+        assert!(codex.get_by_tag("egg").is_none());
 
+        let mut tags: Vec<_> = codex.iter_with_tags().map(|(_, tag, _)| tag).collect();
+        tags.sort();
+        assert_eq!(tags, ["bacon", "spam"]);
+
+        assert_eq!(format!("{:?}", SPAM), "Id(\"spam\")");
+    }
+
+    #[test]
+    fn codex_new() {
+        let horse: Id<MenuItem> = Id::register_tag("horse");
+        let unknown = Id::new(7);
+        let codex = Codex::new(HashMap::from([
+            (horse, MenuItem::new(10)),
+            (unknown, MenuItem::new(11)),
+        ]));
+
+        assert_eq!(codex[&unknown].value, 11);
+        assert_eq!(codex.tag_of(&horse), Some("horse"));
+        assert_eq!(codex.get_by_tag("horse").map(|entry| entry.value), Some(10));
+        assert_eq!(codex.tag_of(&unknown), None);
+    }
+
+    #[test]
+    fn format_extensions() {
+        let extensions = CodexFormat::extensions_for("menu");
+        assert_eq!(extensions, ["menu.toml", "menu.ron", "menu.json"]);
+        assert!(std::ptr::eq(
+            extensions,
+            CodexFormat::extensions_for("menu")
+        ));
+    }
+
+    #[test]
+    fn codex_collision() {
+        let result = Codex::builder_with_capacity(0)
+            .add("bsxjlvga", MenuItem::new(10))
+            .try_add("wuvrhmxa", MenuItem::new(11));
+
+        assert_eq!(
+            result.err(),
+            Some(TagCollision {
+                tag: String::from("wuvrhmxa"),
+                existing: String::from("bsxjlvga"),
+            })
+        );
+    }
+
+    #[test]
+    fn codex_changed() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<Codex<MenuItem>>()
+            .add_plugins(CodexPlugin::<MenuItem>::default());
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<Codex<MenuItem>>>()
+            .add(Codex::from_iter([
+                ("spam", MenuItem::new(10)),
+                ("bacon", MenuItem::new(11)),
+                ("egg", MenuItem::new(12)),
+            ]));
+        app.update();
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<Assets<Codex<MenuItem>>>()
+            .insert(
+                &handle,
+                Codex::from_iter([
+                    ("spam", MenuItem::new(13)),
+                    ("bacon", MenuItem::new(11)),
+                    ("ham", MenuItem::new(14)),
+                ]),
+            );
+        app.update();
+        app.update();
+
+        let events = app.world().resource::<Events<CodexChanged<MenuItem>>>();
+        let mut cursor = events.get_cursor();
+        let changes: Vec<_> = cursor.read(events).collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].codex, handle.id());
+        assert_eq!(changes[0].added, [Id::from_tag("ham")]);
+        assert_eq!(changes[0].removed, [Id::from_tag("egg")]);
+        assert_eq!(changes[0].modified, [SPAM]);
+    }
+
+    fn load_codex(
+        root: Dir,
+        path: &'static str,
+        loader: CodexLoader<RawMenuItem, MenuItem>,
+    ) -> (App, AssetId<Codex<MenuItem>>) {
         let mut app = App::new();
         let memory_asset_reader = MemoryAssetReader { root };
         app.register_asset_source(
@@ -323,25 +559,121 @@ text = 'some other text'
         )
         .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
         .init_asset::<Codex<MenuItem>>()
-        .register_asset_loader(CodexLoader::<RawMenuItem, MenuItem>::default());
+        .register_asset_loader(loader);
         let asset_server = app.world().resource::<AssetServer>().clone();
 
-        let handle: Handle<Codex<MenuItem>> = asset_server.load("some.menu.toml");
+        let handle: Handle<Codex<MenuItem>> = asset_server.load(path);
         let asset_id = handle.id();
         app.world_mut().spawn(CodexHandle(handle));
         for _ in 0..100 {
             app.update();
-            if let Some(codex) = app
-                .world()
-                .resource::<Assets<Codex<MenuItem>>>()
-                .get(asset_id)
+            if asset_server.load_state(asset_id).is_loaded()
+                || asset_server.load_state(asset_id).is_failed()
             {
-                assert_eq!(codex.iter().count(), 2);
-                assert_eq!(codex[&SPAM].value, 13);
-                assert_eq!(codex[&SPAM].text, String::from("some text"));
-                assert_eq!(codex[&SPAM].source, String::from("some.menu.toml"));
                 break;
             }
         }
+        (app, asset_id)
+    }
+
+    #[test]
+    fn codex_loader() {
+        let root = Dir::default();
+        root.insert_asset_text(Path::new("some.menu.toml"), CODEXFILE);
+
+        let (app, asset_id) = load_codex(root, "some.menu.toml", CodexLoader::default());
+        let codex = app
+            .world()
+            .resource::<Assets<Codex<MenuItem>>>()
+            .get(asset_id)
+            .unwrap();
+        assert_eq!(codex.iter().count(), 2);
+        assert_eq!(codex[&SPAM].value, 13);
+        assert_eq!(codex[&SPAM].text, String::from("some text"));
+        assert_eq!(codex[&SPAM].source, String::from("some.menu.toml"));
+    }
+
+    #[test]
+    fn codex_loader_collision() {
+        let root = Dir::default();
+        root.insert_asset_text(
+            Path::new("some.menu.toml"),
+            "[bsxjlvga]\nvalue = 1\ntext = 'a'\n[wuvrhmxa]\nvalue = 2\ntext = 'b'\n",
+        );
+
+        let (app, asset_id) = load_codex(root, "some.menu.toml", CodexLoader::default());
+        let asset_server = app.world().resource::<AssetServer>();
+        assert!(asset_server.load_state(asset_id).is_failed());
+    }
+
+    #[test]
+    fn codex_loader_layers() {
+        let root = Dir::default();
+        root.insert_asset_text(Path::new("some.menu.toml"), CODEXFILE);
+        root.insert_asset_text(
+            Path::new("expansion.menu.toml"),
+            "[bacon]\nvalue = 11\ntext = 'bacon'\n[spam]\nvalue = 14\n",
+        );
+        root.insert_asset_text(Path::new("mod.menu.json"), r#"{ "_remove": ["egg"] }"#);
+
+        let (app, asset_id) = load_codex(
+            root,
+            "some.menu.toml",
+            CodexLoader::with_layers(["expansion.menu.toml", "mod.menu.json"]),
+        );
+        let codex = app
+            .world()
+            .resource::<Assets<Codex<MenuItem>>>()
+            .get(asset_id)
+            .unwrap();
+        assert_eq!(codex.iter().count(), 2);
+        assert_eq!(codex[&SPAM].value, 14);
+        assert_eq!(codex[&SPAM].text, String::from("some text"));
+        assert_eq!(codex.get_by_tag("bacon").map(|entry| entry.value), Some(11));
+        assert!(codex.get_by_tag("egg").is_none());
+    }
+
+    #[test]
+    fn codex_loader_ron() {
+        let root = Dir::default();
+        root.insert_asset_text(
+            Path::new("some.menu.ron"),
+            r#"{
+                "spam": (value: 13, text: "some text"),
+                "egg": (value: 37, text: "some other text"),
+            }"#,
+        );
+
+        let (app, asset_id) = load_codex(root, "some.menu.ron", CodexLoader::default());
+        let codex = app
+            .world()
+            .resource::<Assets<Codex<MenuItem>>>()
+            .get(asset_id)
+            .unwrap();
+        assert_eq!(codex.iter().count(), 2);
+        assert_eq!(codex[&SPAM].value, 13);
+        assert_eq!(codex[&SPAM].source, String::from("some.menu.ron"));
+    }
+
+    #[test]
+    fn codex_loader_json() {
+        let root = Dir::default();
+        root.insert_asset_text(
+            Path::new("some.menu.json"),
+            r#"{
+                "spam": { "value": 13, "text": "some text" },
+                "egg": { "value": 37, "text": "some other text" }
+            }"#,
+        );
+
+        let (app, asset_id) = load_codex(root, "some.menu.json", CodexLoader::default());
+        let codex = app
+            .world()
+            .resource::<Assets<Codex<MenuItem>>>()
+            .get(asset_id)
+            .unwrap();
+        assert_eq!(codex.iter().count(), 2);
+        assert_eq!(codex[&SPAM].value, 13);
+        assert_eq!(codex[&SPAM].source, String::from("some.menu.json"));
     }
 }
@@ -1,8 +1,14 @@
-use super::Codex;
-use bevy_asset::{io::Reader, AssetLoader, LoadContext};
+use super::{layer::merge_layer, Codex};
+use bevy_asset::{io::Reader, AssetLoader, AssetPath, LoadContext, ReadAssetBytesError};
 use bevy_reflect::TypePath;
-use serde::de::{Deserialize, DeserializeSeed, Deserializer, MapAccess, Visitor};
-use std::{fmt, marker::PhantomData};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use std::{
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -13,14 +19,81 @@ pub enum Error {
     Utf8Error(#[from] std::str::Utf8Error),
     #[error(transparent)]
     TomlError(#[from] toml::de::Error),
+    #[error(transparent)]
+    RonError(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    ReadAssetBytesError(#[from] ReadAssetBytesError),
+    #[error("unsupported codex format `{0}`")]
+    UnsupportedFormat(String),
 }
 
 /// Data that is contained in a codex.
 pub trait CodexSource: Send + Sync + 'static {
-    /// Specifices the file extension for codex.
+    /// Specifices the file extension for codex, without the suffix of the format. The codex for
+    /// `creature` is loaded from files ending with `.creature.toml`, `.creature.ron` or
+    /// `.creature.json`.
     const EXTENSION: &'static str;
 }
 
+/// File formats that a codex can be defined in, selected by the file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodexFormat {
+    Toml,
+    Ron,
+    Json,
+}
+
+impl CodexFormat {
+    pub const ALL: [CodexFormat; 3] = [CodexFormat::Toml, CodexFormat::Ron, CodexFormat::Json];
+
+    /// The file extensions of a codex with `base` extension in every format, e.g
+    /// `["creature.toml", "creature.ron", "creature.json"]`.
+    ///
+    /// The asset loader hands out borrowed strings, so they are built once for each base extension
+    /// and kept for the rest of the process.
+    pub fn extensions_for(base: &'static str) -> &'static [&'static str] {
+        static EXTENSIONS: LazyLock<Mutex<HashMap<&'static str, &'static [&'static str]>>> =
+            LazyLock::new(Default::default);
+        let mut extensions = EXTENSIONS.lock().unwrap_or_else(|error| error.into_inner());
+        extensions.entry(base).or_insert_with(|| {
+            Box::leak(
+                Self::ALL
+                    .iter()
+                    .map(|format| {
+                        &*Box::leak(format!("{}.{}", base, format.extension()).into_boxed_str())
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CodexFormat::Toml => "toml",
+            CodexFormat::Ron => "ron",
+            CodexFormat::Json => "json",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?;
+        Self::ALL
+            .into_iter()
+            .find(|format| extension == format.extension())
+    }
+
+    /// Parse the codex data into a table without interpreting the entries.
+    pub(crate) fn parse_table(&self, bytes: &[u8]) -> Result<toml::Table, Error> {
+        Ok(match self {
+            CodexFormat::Toml => toml::from_str(std::str::from_utf8(bytes)?)?,
+            CodexFormat::Ron => ron::de::from_bytes(bytes)?,
+            CodexFormat::Json => serde_json::from_slice(bytes)?,
+        })
+    }
+}
+
 /// Defines how an asset is processed from some raw definition using the LoadContext.
 pub trait FromWithLoadContext<T> {
     fn from_with_load_context(raw: T, load_context: &mut LoadContext) -> Self;
@@ -98,10 +171,12 @@ where
         let mut codex_builder = Codex::builder_with_capacity(access.size_hint().unwrap_or(0));
 
         while let Some((key, value)) = access.next_entry::<String, RawEntry>()? {
-            codex_builder = codex_builder.add(
-                key.as_str(),
-                Entry::from_with_load_context(value, self.load_context),
-            );
+            codex_builder = codex_builder
+                .try_add(
+                    key.as_str(),
+                    Entry::from_with_load_context(value, self.load_context),
+                )
+                .map_err(de::Error::custom)?;
         }
 
         Ok(codex_builder.build())
@@ -109,18 +184,50 @@ where
 }
 
 /// Loads codex assets from TOML-files
+///
+/// The loader can be configured with an ordered list of overlay files that are merged on top of
+/// every codex it loads, see [`merge_layer`] for how layers are combined.
+///
+/// Overlays with a different format than the codex they are applied to are supported, but the
+/// data is then merged as plain tables which means RON enum variants must be written in the map
+/// form, e.g `{ "Srgba": (red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0) }`.
 pub struct CodexLoader<RawEntry, Entry = RawEntry> {
+    extensions: &'static [&'static str],
+    layers: Vec<AssetPath<'static>>,
     _phantom_data: PhantomData<(RawEntry, Entry)>,
 }
 
-impl<RawEntry, Entry> Default for CodexLoader<RawEntry, Entry> {
+impl<RawEntry, Entry> Default for CodexLoader<RawEntry, Entry>
+where
+    Entry: CodexSource,
+{
     fn default() -> Self {
+        Self::with_layers(Vec::<AssetPath<'static>>::new())
+    }
+}
+
+impl<RawEntry, Entry> CodexLoader<RawEntry, Entry>
+where
+    Entry: CodexSource,
+{
+    /// Create a loader that merges `layers` on top of the loaded codex, in order.
+    pub fn with_layers<I, P>(layers: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<AssetPath<'static>>,
+    {
         Self {
+            extensions: CodexFormat::extensions_for(Entry::EXTENSION),
+            layers: layers.into_iter().map(Into::into).collect(),
             _phantom_data: PhantomData,
         }
     }
 }
 
+fn format_of(path: &Path) -> Result<CodexFormat, Error> {
+    CodexFormat::from_path(path).ok_or_else(|| Error::UnsupportedFormat(path.display().to_string()))
+}
+
 impl<RawEntry, Entry> AssetLoader for CodexLoader<RawEntry, Entry>
 where
     Entry: CodexSource + std::fmt::Debug + TypePath + Send + Sync + FromWithLoadContext<RawEntry>,
@@ -131,7 +238,7 @@ where
     type Error = Error;
 
     fn extensions(&self) -> &[&str] {
-        &[Entry::EXTENSION]
+        self.extensions
     }
 
     async fn load(
@@ -142,9 +249,32 @@ where
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let strdata = std::str::from_utf8(&bytes)?;
+        let format = format_of(load_context.path())?;
+        if self.layers.is_empty() {
+            // Deserialize straight from the source to keep line information in errors.
+            let seed = CodexDeserializer::with_load_context(load_context);
+            return Ok(match format {
+                CodexFormat::Toml => {
+                    seed.deserialize(toml::de::Deserializer::new(std::str::from_utf8(&bytes)?))?
+                }
+                CodexFormat::Ron => ron::Options::default().from_bytes_seed(&bytes, seed)?,
+                CodexFormat::Json => {
+                    let mut deserializer = serde_json::Deserializer::from_slice(&bytes);
+                    let codex = seed.deserialize(&mut deserializer)?;
+                    deserializer.end()?;
+                    codex
+                }
+            });
+        }
+
+        let mut table = format.parse_table(&bytes)?;
+        for layer in &self.layers {
+            let layer_format = format_of(layer.path())?;
+            let bytes = load_context.read_asset_bytes(layer.clone()).await?;
+            merge_layer(&mut table, layer_format.parse_table(&bytes)?);
+        }
         let codex: Self::Asset = CodexDeserializer::with_load_context(load_context)
-            .deserialize(toml::de::Deserializer::new(strdata))?;
+            .deserialize(toml::Value::Table(table))?;
         Ok(codex)
     }
 }
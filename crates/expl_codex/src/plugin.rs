@@ -0,0 +1,80 @@
+use super::{Codex, CodexChanged};
+use bevy_app::prelude::*;
+use bevy_asset::{AssetEvent, AssetId, Assets};
+use bevy_ecs::prelude::*;
+use bevy_reflect::TypePath;
+use std::{collections::HashMap, marker::PhantomData};
+
+/// Tracks reloads of [`Codex<Entry>`] assets and sends [`CodexChanged<Entry>`] events.
+pub struct CodexPlugin<Entry> {
+    _phantom_data: PhantomData<Entry>,
+}
+
+impl<Entry> Default for CodexPlugin<Entry> {
+    fn default() -> Self {
+        Self {
+            _phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<Entry> Plugin for CodexPlugin<Entry>
+where
+    Entry: TypePath + Send + Sync + PartialEq,
+{
+    fn build(&self, app: &mut App) {
+        app.add_event::<CodexChanged<Entry>>().add_systems(
+            PreUpdate,
+            detect_codex_changes::<Entry>.run_if(on_event::<AssetEvent<Codex<Entry>>>),
+        );
+    }
+}
+
+/// The last seen version of each codex, cheap to keep around as codex data is shared.
+struct CodexSnapshots<Entry>(HashMap<AssetId<Codex<Entry>>, Codex<Entry>>)
+where
+    Entry: TypePath + Send + Sync;
+
+impl<Entry> Default for CodexSnapshots<Entry>
+where
+    Entry: TypePath + Send + Sync,
+{
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+fn detect_codex_changes<Entry>(
+    mut asset_events: EventReader<AssetEvent<Codex<Entry>>>,
+    mut codex_changed_events: EventWriter<CodexChanged<Entry>>,
+    mut snapshots: Local<CodexSnapshots<Entry>>,
+    codex_assets: Res<Assets<Codex<Entry>>>,
+) where
+    Entry: TypePath + Send + Sync + PartialEq,
+{
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::Added { id } => {
+                if let Some(codex) = codex_assets.get(*id) {
+                    snapshots.0.insert(*id, codex.clone());
+                }
+            }
+            AssetEvent::Modified { id } => {
+                let Some(codex) = codex_assets.get(*id) else {
+                    continue;
+                };
+                let empty = Codex::builder_with_capacity(0).build();
+                let previous = snapshots.0.get(id).unwrap_or(&empty);
+                let changed = CodexChanged::between(*id, previous, codex);
+                snapshots.0.insert(*id, codex.clone());
+                if !changed.is_empty() {
+                    codex_changed_events.write(changed);
+                }
+            }
+            AssetEvent::Removed { id } => {
+                snapshots.0.remove(id);
+            }
+            AssetEvent::Unused { .. } | AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::Id;
+use std::{
+    any::type_name,
+    collections::HashMap,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+type Key = (&'static str, u64);
+
+/// Process wide mapping from identifiers back to the tags they were created from.
+///
+/// The registry is keyed on the type name of the phantom type as well as the hash value so that
+/// ids of different types never shadow each other.
+static TAGS: LazyLock<RwLock<HashMap<Key, Arc<str>>>> = LazyLock::new(Default::default);
+
+fn key<T>(id: &Id<T>) -> Key {
+    (type_name::<T>(), id.value)
+}
+
+pub(crate) fn register<T>(id: &Id<T>, tag: &str) {
+    let Ok(mut tags) = TAGS.write() else {
+        return;
+    };
+    tags.entry(key(id)).or_insert_with(|| Arc::from(tag));
+}
+
+pub(crate) fn lookup<T>(id: &Id<T>) -> Option<Arc<str>> {
+    TAGS.read().ok()?.get(&key(id)).cloned()
+}
@@ -0,0 +1,854 @@
+use super::{layer::REMOVE_KEY, loader::Error, CodexFormat};
+use bevy_reflect::{EnumInfo, NamedField, Reflect, TypeInfo, Typed, VariantInfo};
+use serde_json::{json, Value as Json};
+use std::{collections::HashSet, fmt, ops::Range};
+use toml_edit::{ImDocument, Item, Table};
+
+/// Custom attribute for string fields that hold the path of an asset.
+///
+/// ```
+/// use bevy_reflect::Reflect;
+/// use expl_codex::AssetReference;
+///
+/// #[derive(Reflect)]
+/// struct RawTree {
+///     #[reflect(@AssetReference)]
+///     mesh: String,
+/// }
+/// ```
+#[derive(Reflect, Debug)]
+pub struct AssetReference;
+
+/// Custom attribute for fields that may be left out of an entry, e.g because they are marked with
+/// `#[serde(default)]`.
+#[derive(Reflect, Debug)]
+pub struct Optional;
+
+/// Describes the format of a raw codex entry as it is written in a codex file.
+///
+/// The schema is derived from the reflected type information and follows how serde represents the
+/// type, e.g enums are externally tagged.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schema {
+    Boolean,
+    Integer { min: i64, max: i64 },
+    Number,
+    String,
+    AssetReference,
+    Character,
+    Array(Box<Schema>),
+    Object(Vec<Field>),
+    Enum(Vec<Variant>),
+    Any,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    pub schema: Schema,
+    pub optional: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    pub name: &'static str,
+    /// Schema of the value of the variant, `None` for unit variants.
+    pub content: Option<Schema>,
+}
+
+/// Serde leaves out `None` and represents `Some` as the inner value.
+fn is_option(info: &EnumInfo) -> bool {
+    info.type_path_table().module_path() == Some("core::option")
+}
+
+fn integer(min: i128, max: i128) -> Schema {
+    let clamp = |value: i128| value.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+    Schema::Integer {
+        min: clamp(min),
+        max: clamp(max),
+    }
+}
+
+impl Schema {
+    pub fn of<T: Typed>() -> Self {
+        Self::from_type_info(T::type_info())
+    }
+
+    pub fn from_type_info(info: &TypeInfo) -> Self {
+        match info {
+            TypeInfo::Struct(info) => Schema::Object(info.iter().map(Field::from_named).collect()),
+            TypeInfo::TupleStruct(info) if info.field_len() == 1 => {
+                Self::from_optional_info(info.field_at(0).and_then(|field| field.type_info()))
+            }
+            TypeInfo::List(info) => {
+                Schema::Array(Box::new(Self::from_optional_info(info.item_info())))
+            }
+            TypeInfo::Array(info) => {
+                Schema::Array(Box::new(Self::from_optional_info(info.item_info())))
+            }
+            TypeInfo::Enum(info) if is_option(info) => {
+                Self::from_optional_info(match info.variant("Some") {
+                    Some(VariantInfo::Tuple(variant)) => {
+                        variant.field_at(0).and_then(|field| field.type_info())
+                    }
+                    _ => None,
+                })
+            }
+            TypeInfo::Enum(info) => Schema::Enum(info.iter().map(Variant::from_info).collect()),
+            TypeInfo::Opaque(info) => {
+                let ty = info.ty();
+                if ty.is::<bool>() {
+                    Schema::Boolean
+                } else if ty.is::<char>() {
+                    Schema::Character
+                } else if ty.is::<String>() {
+                    Schema::String
+                } else if ty.is::<f32>() || ty.is::<f64>() {
+                    Schema::Number
+                } else if ty.is::<u8>() {
+                    integer(0, u8::MAX.into())
+                } else if ty.is::<u16>() {
+                    integer(0, u16::MAX.into())
+                } else if ty.is::<u32>() {
+                    integer(0, u32::MAX.into())
+                } else if ty.is::<u64>() || ty.is::<usize>() {
+                    integer(0, u64::MAX.into())
+                } else if ty.is::<i8>() {
+                    integer(i8::MIN.into(), i8::MAX.into())
+                } else if ty.is::<i16>() {
+                    integer(i16::MIN.into(), i16::MAX.into())
+                } else if ty.is::<i32>() {
+                    integer(i32::MIN.into(), i32::MAX.into())
+                } else if ty.is::<i64>() || ty.is::<isize>() {
+                    integer(i64::MIN.into(), i64::MAX.into())
+                } else {
+                    Schema::Any
+                }
+            }
+            _ => Schema::Any,
+        }
+    }
+
+    fn from_optional_info(info: Option<&TypeInfo>) -> Self {
+        info.map_or(Schema::Any, Self::from_type_info)
+    }
+
+    /// Convert to a JSON Schema describing a single value.
+    pub fn to_json(&self) -> Json {
+        match self {
+            Schema::Boolean => json!({ "type": "boolean" }),
+            Schema::Integer { min, max } => {
+                json!({ "type": "integer", "minimum": min, "maximum": max })
+            }
+            Schema::Number => json!({ "type": "number" }),
+            Schema::String => json!({ "type": "string" }),
+            Schema::AssetReference => {
+                json!({ "type": "string", "description": "Path of an asset" })
+            }
+            Schema::Character => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+            Schema::Array(item) => json!({ "type": "array", "items": item.to_json() }),
+            Schema::Object(fields) => {
+                let properties: serde_json::Map<String, Json> = fields
+                    .iter()
+                    .map(|field| (field.name.to_string(), field.schema.to_json()))
+                    .collect();
+                let required: Vec<&str> = fields
+                    .iter()
+                    .filter(|field| !field.optional)
+                    .map(|field| field.name)
+                    .collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            Schema::Enum(variants) => {
+                let one_of: Vec<Json> = variants
+                    .iter()
+                    .flat_map(|variant| {
+                        let tagged = |content: Json| {
+                            json!({
+                                "type": "object",
+                                "properties": { variant.name: content },
+                                "required": [variant.name],
+                                "additionalProperties": false,
+                            })
+                        };
+                        match &variant.content {
+                            None => vec![
+                                json!({ "const": variant.name }),
+                                tagged(json!({ "type": "object", "maxProperties": 0 })),
+                            ],
+                            Some(content) => vec![tagged(content.to_json())],
+                        }
+                    })
+                    .collect();
+                json!({ "oneOf": one_of })
+            }
+            Schema::Any => json!({}),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Schema::Boolean => "a boolean",
+            Schema::Integer { .. } => "an integer",
+            Schema::Number => "a number",
+            Schema::String => "a string",
+            Schema::AssetReference => "an asset path",
+            Schema::Character => "a single character",
+            Schema::Array(_) => "an array",
+            Schema::Object(_) => "a table",
+            Schema::Enum(_) => "a variant name or a table with a single variant",
+            Schema::Any => "any value",
+        }
+    }
+}
+
+impl Field {
+    fn from_named(field: &NamedField) -> Self {
+        let schema = if field.has_attribute::<AssetReference>() {
+            Schema::AssetReference
+        } else {
+            Schema::from_optional_info(field.type_info())
+        };
+        Self {
+            name: field.name(),
+            schema,
+            optional: field.has_attribute::<Optional>()
+                || matches!(field.type_info(), Some(TypeInfo::Enum(info)) if is_option(info)),
+        }
+    }
+}
+
+impl Variant {
+    fn from_info(info: &VariantInfo) -> Self {
+        let content = match info {
+            VariantInfo::Unit(_) => None,
+            VariantInfo::Tuple(info) if info.field_len() == 1 => Some(Schema::from_optional_info(
+                info.field_at(0).and_then(|field| field.type_info()),
+            )),
+            VariantInfo::Tuple(_) => Some(Schema::Array(Box::new(Schema::Any))),
+            VariantInfo::Struct(info) => {
+                Some(Schema::Object(info.iter().map(Field::from_named).collect()))
+            }
+        };
+        Self {
+            name: info.name(),
+            content,
+        }
+    }
+}
+
+/// JSON Schema of a codex file where each entry follows the `entry` schema.
+pub fn codex_json_schema(title: &str, entry: &Schema) -> Json {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": title,
+        "type": "object",
+        "properties": {
+            REMOVE_KEY: { "type": "array", "items": { "type": "string" } },
+        },
+        "additionalProperties": entry.to_json(),
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// A problem found when validating a codex file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Where in the file the problem is. Only available for TOML files and for syntax errors.
+    pub location: Option<Location>,
+    /// Dotted path to the offending value, e.g `slime.attack.low`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{}:{}: ", location.line, location.column)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Parsed codex data annotated with the location in the source.
+struct Node {
+    span: Option<Range<usize>>,
+    value: NodeValue,
+}
+
+enum NodeValue {
+    Boolean,
+    Integer(i64),
+    Float,
+    String(String),
+    Datetime,
+    Array(Vec<Node>),
+    Table(Vec<(String, Option<Range<usize>>, Node)>),
+}
+
+impl NodeValue {
+    fn describe(&self) -> &'static str {
+        match self {
+            NodeValue::Boolean => "a boolean",
+            NodeValue::Integer(_) => "an integer",
+            NodeValue::Float => "a float",
+            NodeValue::String(_) => "a string",
+            NodeValue::Datetime => "a datetime",
+            NodeValue::Array(_) => "an array",
+            NodeValue::Table(_) => "a table",
+        }
+    }
+}
+
+impl Node {
+    fn from_item(item: &Item) -> Self {
+        match item {
+            Item::None => Self {
+                span: None,
+                value: NodeValue::Table(Vec::new()),
+            },
+            Item::Value(value) => Self::from_value(value),
+            Item::Table(table) => Self::from_table(table),
+            Item::ArrayOfTables(array) => Self {
+                span: array.span(),
+                value: NodeValue::Array(array.iter().map(Self::from_table).collect()),
+            },
+        }
+    }
+
+    fn from_table(table: &Table) -> Self {
+        Self {
+            span: table.span(),
+            value: NodeValue::Table(
+                table
+                    .iter()
+                    .map(|(key, item)| {
+                        let key_span = table.key(key).and_then(|key| key.span());
+                        (key.to_string(), key_span, Self::from_item(item))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn from_value(value: &toml_edit::Value) -> Self {
+        use toml_edit::Value;
+        let node_value = match value {
+            Value::String(value) => NodeValue::String(value.value().clone()),
+            Value::Integer(value) => NodeValue::Integer(*value.value()),
+            Value::Float(_) => NodeValue::Float,
+            Value::Boolean(_) => NodeValue::Boolean,
+            Value::Datetime(_) => NodeValue::Datetime,
+            Value::Array(array) => NodeValue::Array(array.iter().map(Self::from_value).collect()),
+            Value::InlineTable(table) => NodeValue::Table(
+                table
+                    .iter()
+                    .map(|(key, value)| {
+                        let key_span = table.key(key).and_then(|key| key.span());
+                        (key.to_string(), key_span, Self::from_value(value))
+                    })
+                    .collect(),
+            ),
+        };
+        Self {
+            span: value.span(),
+            value: node_value,
+        }
+    }
+
+    /// Convert data parsed without location information.
+    fn from_toml(value: toml::Value) -> Self {
+        use toml::Value;
+        let node_value = match value {
+            Value::String(value) => NodeValue::String(value),
+            Value::Integer(value) => NodeValue::Integer(value),
+            Value::Float(_) => NodeValue::Float,
+            Value::Boolean(_) => NodeValue::Boolean,
+            Value::Datetime(_) => NodeValue::Datetime,
+            Value::Array(array) => {
+                NodeValue::Array(array.into_iter().map(Self::from_toml).collect())
+            }
+            Value::Table(table) => NodeValue::Table(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (key, None, Self::from_toml(value)))
+                    .collect(),
+            ),
+        };
+        Self {
+            span: None,
+            value: node_value,
+        }
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Checks codex files against the schema of their entries.
+///
+/// Files of the same codex should be validated in the order they are layered, so that tags listed
+/// under [`REMOVE_KEY`] can be checked against the entries defined by earlier files.
+pub struct Validator<'a> {
+    schema: &'a Schema,
+    asset_exists: Box<dyn Fn(&str) -> bool + 'a>,
+    tags: HashSet<String>,
+}
+
+impl<'a> Validator<'a> {
+    /// Create a validator for entries following `schema`. Asset references are checked with the
+    /// `asset_exists` callback.
+    pub fn new(schema: &'a Schema, asset_exists: impl Fn(&str) -> bool + 'a) -> Self {
+        Self {
+            schema,
+            asset_exists: Box::new(asset_exists),
+            tags: HashSet::new(),
+        }
+    }
+
+    /// Validate the contents of a single codex file.
+    pub fn validate(&mut self, format: CodexFormat, source: &str) -> Vec<Diagnostic> {
+        let root = match format {
+            CodexFormat::Toml => match ImDocument::parse(source) {
+                Ok(document) => Node::from_table(document.as_table()),
+                Err(err) => {
+                    return vec![Diagnostic {
+                        location: err
+                            .span()
+                            .map(|span| Location::from_offset(source, span.start)),
+                        path: String::new(),
+                        message: err.message().trim().to_string(),
+                    }]
+                }
+            },
+            format => match format.parse_table(source.as_bytes()) {
+                Ok(table) => Node::from_toml(toml::Value::Table(table)),
+                Err(err) => {
+                    let (location, message) = match err {
+                        Error::RonError(err) => (
+                            Some(Location {
+                                line: err.position.line,
+                                column: err.position.col,
+                            }),
+                            err.code.to_string(),
+                        ),
+                        Error::JsonError(err) => (
+                            Some(Location {
+                                line: err.line(),
+                                column: err.column(),
+                            }),
+                            err.to_string(),
+                        ),
+                        err => (None, err.to_string()),
+                    };
+                    return vec![Diagnostic {
+                        location,
+                        path: String::new(),
+                        message,
+                    }];
+                }
+            },
+        };
+
+        let mut walk = Walk {
+            validator: self,
+            source,
+            diagnostics: Vec::new(),
+            partial: false,
+        };
+        walk.check_codex(root);
+        walk.diagnostics
+    }
+}
+
+struct Walk<'v, 'a, 's> {
+    validator: &'v mut Validator<'a>,
+    source: &'s str,
+    diagnostics: Vec<Diagnostic>,
+    /// Whether the entry being checked is merged on top of an entry from an earlier layer, so
+    /// fields can be left out.
+    partial: bool,
+}
+
+impl Walk<'_, '_, '_> {
+    fn report(&mut self, span: Option<&Range<usize>>, path: &str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            location: span.map(|span| Location::from_offset(self.source, span.start)),
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn check_codex(&mut self, root: Node) {
+        let NodeValue::Table(entries) = root.value else {
+            self.report(root.span.as_ref(), "", "expected a table of entries".into());
+            return;
+        };
+
+        // Removals are applied before the entries of the same layer are merged.
+        let (removals, entries): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(key, _, _)| key == REMOVE_KEY);
+        for (key, key_span, node) in removals {
+            let NodeValue::Array(tags) = node.value else {
+                self.report(key_span.as_ref(), &key, "expected an array of tags".into());
+                continue;
+            };
+            for (index, tag) in tags.iter().enumerate() {
+                let path = format!("{}[{}]", key, index);
+                match &tag.value {
+                    NodeValue::String(tag_name) => {
+                        if !self.validator.tags.remove(tag_name) {
+                            self.report(
+                                tag.span.as_ref(),
+                                &path,
+                                format!("removes `{}` which is not defined", tag_name),
+                            );
+                        }
+                    }
+                    value => self.report(
+                        tag.span.as_ref(),
+                        &path,
+                        format!("expected a string, found {}", value.describe()),
+                    ),
+                }
+            }
+        }
+
+        let schema = self.validator.schema;
+        for (tag, key_span, node) in entries {
+            self.partial = self.validator.tags.contains(&tag);
+            self.check(schema, &node, key_span.as_ref(), &tag);
+            self.validator.tags.insert(tag);
+        }
+        self.partial = false;
+    }
+
+    /// Check a single value. The span of the key is used for reporting problems with values that
+    /// don't have a location of their own.
+    fn check(&mut self, schema: &Schema, node: &Node, key_span: Option<&Range<usize>>, path: &str) {
+        let span = node.span.as_ref().or(key_span);
+        match (schema, &node.value) {
+            (Schema::Any, _)
+            | (Schema::Boolean, NodeValue::Boolean)
+            | (Schema::Number, NodeValue::Integer(_) | NodeValue::Float)
+            | (Schema::String, NodeValue::String(_)) => {}
+            (Schema::Integer { min, max }, NodeValue::Integer(value)) => {
+                if value < min || value > max {
+                    self.report(
+                        span,
+                        path,
+                        format!("{} is out of the range {}..={}", value, min, max),
+                    );
+                }
+            }
+            (Schema::AssetReference, NodeValue::String(asset)) => {
+                if !(self.validator.asset_exists)(asset) {
+                    self.report(span, path, format!("asset `{}` does not exist", asset));
+                }
+            }
+            (Schema::Character, NodeValue::String(value)) => {
+                if value.chars().count() != 1 {
+                    self.report(span, path, "expected a single character".into());
+                }
+            }
+            (Schema::Array(item), NodeValue::Array(items)) => {
+                for (index, child) in items.iter().enumerate() {
+                    self.check(item, child, span, &format!("{}[{}]", path, index));
+                }
+            }
+            (Schema::Object(fields), NodeValue::Table(entries)) => {
+                for (key, child_key_span, child) in entries {
+                    let child_path = join_path(path, key);
+                    match fields.iter().find(|field| field.name == key) {
+                        Some(field) => {
+                            self.check(&field.schema, child, child_key_span.as_ref(), &child_path)
+                        }
+                        None => self.report(
+                            child_key_span.as_ref().or(child.span.as_ref()),
+                            &child_path,
+                            format!("unknown key `{}`", key),
+                        ),
+                    }
+                }
+                let partial = self.partial;
+                for field in fields.iter().filter(|field| !field.optional && !partial) {
+                    if !entries.iter().any(|(key, _, _)| key == field.name) {
+                        self.report(span, path, format!("missing field `{}`", field.name));
+                    }
+                }
+            }
+            (Schema::Enum(variants), NodeValue::String(name)) => {
+                match variants.iter().find(|variant| variant.name == name) {
+                    Some(Variant { content: None, .. }) => {}
+                    Some(_) => self.report(span, path, format!("variant `{}` needs a value", name)),
+                    None => self.report(span, path, unknown_variant(name, variants)),
+                }
+            }
+            (Schema::Enum(variants), NodeValue::Table(entries)) if entries.len() == 1 => {
+                let (name, variant_key_span, content) = &entries[0];
+                let variant_path = join_path(path, name);
+                let variant_key_span = variant_key_span.as_ref().or(span);
+                match variants.iter().find(|variant| variant.name == name) {
+                    Some(Variant {
+                        content: Some(schema),
+                        ..
+                    }) => self.check(schema, content, variant_key_span, &variant_path),
+                    Some(Variant { content: None, .. }) => {
+                        if !matches!(&content.value, NodeValue::Table(fields) if fields.is_empty())
+                        {
+                            self.report(
+                                variant_key_span,
+                                &variant_path,
+                                format!("variant `{}` does not take a value", name),
+                            );
+                        }
+                    }
+                    None => self.report(variant_key_span, path, unknown_variant(name, variants)),
+                }
+            }
+            (schema, value) => self.report(
+                span,
+                path,
+                format!("expected {}, found {}", schema.describe(), value.describe()),
+            ),
+        }
+    }
+}
+
+fn unknown_variant(name: &str, variants: &[Variant]) -> String {
+    let names: Vec<&str> = variants.iter().map(|variant| variant.name).collect();
+    format!(
+        "unknown variant `{}`, expected one of {}",
+        name,
+        names.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Reflect)]
+    enum Kind {
+        Slime,
+        Undead { level: u8 },
+    }
+
+    #[allow(dead_code)]
+    #[derive(Reflect)]
+    struct RawMonster {
+        symbol: char,
+        health: u16,
+        speed: f32,
+        #[reflect(@AssetReference)]
+        mesh: String,
+        #[reflect(@Optional)]
+        kinds: Vec<Kind>,
+        title: Option<String>,
+    }
+
+    fn validate(validator: &mut Validator, format: CodexFormat, source: &str) -> Vec<String> {
+        validator
+            .validate(format, source)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn json_schema() {
+        let schema = codex_json_schema("monster", &Schema::of::<RawMonster>());
+        let entry = &schema["additionalProperties"];
+
+        assert_eq!(
+            entry["properties"]["health"],
+            json!({ "type": "integer", "minimum": 0, "maximum": 65535 })
+        );
+        assert_eq!(
+            entry["required"],
+            json!(["symbol", "health", "speed", "mesh"])
+        );
+        assert_eq!(entry["properties"]["title"], json!({ "type": "string" }));
+        assert_eq!(
+            entry["properties"]["kinds"]["items"]["oneOf"][0],
+            json!({ "const": "Slime" })
+        );
+    }
+
+    #[test]
+    fn validate_valid() {
+        let schema = Schema::of::<RawMonster>();
+        let mut validator = Validator::new(&schema, |asset| asset == "blob.obj");
+        let source = "[slime]
+symbol = 's'
+health = 10
+speed = 1
+mesh = 'blob.obj'
+kinds = ['Slime', { Undead = { level = 2 } }]
+title = 'King'
+
+[bat]
+symbol = 'b'
+health = 4
+speed = 2.5
+mesh = 'blob.obj'
+
+[[bat.kinds]]
+Slime = {}
+";
+
+        assert_eq!(
+            validate(&mut validator, CodexFormat::Toml, source),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn validate_errors() {
+        let schema = Schema::of::<RawMonster>();
+        let mut validator = Validator::new(&schema, |asset| asset == "blob.obj");
+        let source = "[slime]
+symbol = 'sl'
+health = -1
+speed = 'fast'
+mesh = 'slime.obj'
+kinds = [{ Undead = { level = 2, name = 'bob' } }, 'Ghost']
+colour = 'green'
+
+[ghost]
+symbol = 'g'
+";
+
+        assert_eq!(
+            validate(&mut validator, CodexFormat::Toml, source),
+            vec![
+                "2:10: slime.symbol: expected a single character",
+                "3:10: slime.health: -1 is out of the range 0..=65535",
+                "4:9: slime.speed: expected a number, found a string",
+                "5:8: slime.mesh: asset `slime.obj` does not exist",
+                "6:34: slime.kinds[0].Undead.name: unknown key `name`",
+                "6:52: slime.kinds[1]: unknown variant `Ghost`, expected one of Slime, Undead",
+                "7:1: slime.colour: unknown key `colour`",
+                "9:1: ghost: missing field `health`",
+                "9:1: ghost: missing field `speed`",
+                "9:1: ghost: missing field `mesh`",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_syntax_error() {
+        let schema = Schema::of::<RawMonster>();
+        let mut validator = Validator::new(&schema, |_| true);
+
+        let diagnostics = validator.validate(CodexFormat::Toml, "[slime]\nhealth = \n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].location,
+            Some(Location {
+                line: 2,
+                column: 10
+            })
+        );
+
+        let diagnostics = validator.validate(CodexFormat::Json, "{\n  \"slime\": \n}");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].location,
+            Some(Location { line: 3, column: 1 })
+        );
+    }
+
+    #[test]
+    fn validate_layers() {
+        let schema = Schema::of::<RawMonster>();
+        let mut validator = Validator::new(&schema, |_| true);
+        let base = "[slime]
+symbol = 's'
+health = 10
+speed = 1
+mesh = 'blob.obj'
+";
+
+        assert!(validator.validate(CodexFormat::Toml, base).is_empty());
+        assert_eq!(
+            validate(
+                &mut validator,
+                CodexFormat::Json,
+                r#"{ "_remove": ["slime", "ghost"], "bat": { "symbol": "b", "health": "many" } }"#,
+            ),
+            vec![
+                "_remove[1]: removes `ghost` which is not defined",
+                "bat.health: expected an integer, found a string",
+                "bat: missing field `speed`",
+                "bat: missing field `mesh`",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_overlay_override() {
+        let schema = Schema::of::<RawMonster>();
+        let mut validator = Validator::new(&schema, |_| true);
+        let base = "[slime]
+symbol = 's'
+health = 10
+speed = 1
+mesh = 'blob.obj'
+";
+
+        assert!(validator.validate(CodexFormat::Toml, base).is_empty());
+        assert_eq!(
+            validate(
+                &mut validator,
+                CodexFormat::Toml,
+                "[slime]\nhealth = 20\n\n[bat]\nhealth = 'many'\n",
+            ),
+            vec![
+                "5:10: bat.health: expected an integer, found a string",
+                "4:1: bat: missing field `symbol`",
+                "4:1: bat: missing field `speed`",
+                "4:1: bat: missing field `mesh`",
+            ]
+        );
+        assert_eq!(
+            validate(
+                &mut validator,
+                CodexFormat::Toml,
+                "[slime]\nspeed = 'fast'\n"
+            ),
+            vec!["2:9: slime.speed: expected a number, found a string"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use expl_databinding::{DataBindingAppExt, DataBindingExt, DataBindingPlugin, DataBindingUpdate};
+
+const SINKS_PER_SOURCE: usize = 4;
+
+#[derive(Component)]
+struct Temperature(u32);
+
+#[derive(Component)]
+struct Display(u32);
+
+#[derive(Clone, Copy, Debug)]
+enum Propagation {
+    /// Hand-written system using `DataBindingUpdate`.
+    Polling,
+    /// Generated system for closure bindings.
+    PollingWith,
+    /// Observers for closure bindings.
+    Observer,
+}
+
+fn update_display(
+    mut data_binding_update: DataBindingUpdate<&Temperature, &mut Display, Changed<Temperature>>,
+) {
+    data_binding_update.for_each(|temp, display| {
+        display.0 = temp.0;
+    });
+}
+
+fn setup(propagation: Propagation, bindings: usize) -> (App, Vec<Entity>) {
+    let mut app = App::new();
+    app.add_plugins(DataBindingPlugin);
+    match propagation {
+        Propagation::Polling => {
+            app.add_systems(Update, update_display);
+        }
+        Propagation::PollingWith => {
+            app.register_data_binding::<Temperature, Display>();
+        }
+        Propagation::Observer => {
+            app.observe_data_binding::<Temperature, Display>();
+        }
+    }
+
+    let mut commands = app.world_mut().commands();
+    let sources: Vec<Entity> = (0..bindings / SINKS_PER_SOURCE)
+        .map(|index| {
+            let source = commands.spawn(Temperature(index as u32)).id();
+            for _ in 0..SINKS_PER_SOURCE {
+                let mut sink = commands.spawn(Display(0));
+                match propagation {
+                    Propagation::Polling => sink.bind_to(source),
+                    Propagation::PollingWith | Propagation::Observer => {
+                        sink.bind_to_with(source, |temp: &Temperature, display: &mut Display| {
+                            display.0 = temp.0;
+                        })
+                    }
+                };
+            }
+            source
+        })
+        .collect();
+    app.world_mut().flush();
+    app.update();
+    (app, sources)
+}
+
+fn change_sources(app: &mut App, sources: &[Entity], round: u32) {
+    // Change one percent of the sources each frame.
+    for &source in sources.iter().step_by(100) {
+        if let Some(mut temp) = app.world_mut().get_mut::<Temperature>(source) {
+            temp.0 = round;
+        }
+    }
+}
+
+pub fn benchmark_changed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("changed");
+    for bindings in [1000, 4000, 16000] {
+        for propagation in [
+            Propagation::Polling,
+            Propagation::PollingWith,
+            Propagation::Observer,
+        ] {
+            let (mut app, sources) = setup(propagation, bindings);
+            let mut round = 0;
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", propagation), bindings),
+                &bindings,
+                |b, _| {
+                    b.iter(|| {
+                        round += 1;
+                        change_sources(&mut app, &sources, round);
+                        app.update();
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+pub fn benchmark_idle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("idle");
+    for bindings in [1000, 4000, 16000] {
+        for propagation in [
+            Propagation::Polling,
+            Propagation::PollingWith,
+            Propagation::Observer,
+        ] {
+            let (mut app, _) = setup(propagation, bindings);
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", propagation), bindings),
+                &bindings,
+                |b, _| b.iter(|| app.update()),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_changed, benchmark_idle);
+criterion_main!(benches);
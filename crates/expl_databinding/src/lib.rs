@@ -1,26 +1,44 @@
 use bevy_app::prelude::*;
 use bevy_ecs::{
+    component::Mutable,
     prelude::*,
-    query::{QueryData, QueryFilter},
+    query::{QueryData, QueryEntityError, QueryFilter, ReadOnlyQueryData},
     system::{Command, EntityCommands, SystemParam},
 };
 use bevy_reflect::prelude::*;
 use core::slice;
 use smallvec::SmallVec;
 
+mod observer;
+
 pub struct DataBindingPlugin;
 
 impl Plugin for DataBindingPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<DataBindings>()
             .add_event::<DataBindingExpired>()
+            .configure_sets(
+                PostUpdate,
+                (DataBindingSet::Aggregate, DataBindingSet::Propagate).chain(),
+            )
             .add_systems(
                 PostUpdate,
-                remove_expired_data_bindings.run_if(on_event::<DataBindingExpired>),
+                remove_expired_data_bindings
+                    .run_if(on_event::<DataBindingExpired>)
+                    .after(DataBindingSet::Propagate),
             );
     }
 }
 
+/// Sets of the systems added for bindings, both run in `PostUpdate`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DataBindingSet {
+    /// Computes [`Aggregate`] resources.
+    Aggregate,
+    /// Updates the sinks of bindings made with closures.
+    Propagate,
+}
+
 #[derive(Event)]
 struct DataBindingExpired {
     source: Entity,
@@ -72,8 +90,61 @@ impl Command for Bind {
     }
 }
 
+type UpdateFn<Source, Sink> = Box<dyn Fn(&Source, &mut Sink) + Send + Sync>;
+
+/// Binding of the `Sink` component of an entity to the `Source` component of another entity.
+///
+/// The sink is updated by a system that is added for each pair of components with
+/// [`DataBindingAppExt::register_data_binding`].
+#[derive(Component)]
+pub struct DataBindingWith<Source, Sink>
+where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    source: Entity,
+    update: UpdateFn<Source, Sink>,
+}
+
+/// Binding of the `Sink` component of an entity to the resource `R`.
+///
+/// The sink is updated by a system that is added for each pair of resource and component with
+/// [`DataBindingAppExt::register_resource_binding`].
+#[derive(Component)]
+pub struct ResourceBindingWith<R, Sink>
+where
+    R: Resource,
+    Sink: Component<Mutability = Mutable>,
+{
+    update: UpdateFn<R, Sink>,
+}
+
+/// Resource computed from all entities that match a query, e.g the number of living characters.
+///
+/// The resource is recomputed each frame by a system added with
+/// [`DataBindingAppExt::init_aggregate`], but only marked as changed when the value differs so
+/// that sinks bound to it are left alone otherwise.
+pub trait Aggregate: Resource + PartialEq + Default {
+    type Data: ReadOnlyQueryData + 'static;
+    type Filter: QueryFilter + 'static;
+
+    fn aggregate(query: &Query<'_, '_, Self::Data, Self::Filter>) -> Self;
+}
+
 pub trait DataBindingExt {
     fn bind_to(&mut self, source: Entity) -> &mut Self;
+
+    fn bind_to_with<Source, Sink, F>(&mut self, source: Entity, f: F) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>,
+        F: Fn(&Source, &mut Sink) + Send + Sync + 'static;
+
+    fn bind_to_resource_with<R, Sink, F>(&mut self, f: F) -> &mut Self
+    where
+        R: Resource,
+        Sink: Component<Mutability = Mutable>,
+        F: Fn(&R, &mut Sink) + Send + Sync + 'static;
 }
 
 impl DataBindingExt for EntityCommands<'_> {
@@ -83,6 +154,117 @@ impl DataBindingExt for EntityCommands<'_> {
         self.commands().queue(Bind { source, sink });
         self
     }
+
+    /// Bind the entity to another entity `source` and keep the `Sink` component of the entity up
+    /// to date with the `Source` component of `source` using `f`.
+    fn bind_to_with<Source, Sink, F>(&mut self, source: Entity, f: F) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>,
+        F: Fn(&Source, &mut Sink) + Send + Sync + 'static,
+    {
+        self.bind_to(source)
+            .insert(DataBindingWith::<Source, Sink> {
+                source,
+                update: Box::new(f),
+            })
+    }
+
+    /// Keep the `Sink` component of the entity up to date with the resource `R` using `f`.
+    fn bind_to_resource_with<R, Sink, F>(&mut self, f: F) -> &mut Self
+    where
+        R: Resource,
+        Sink: Component<Mutability = Mutable>,
+        F: Fn(&R, &mut Sink) + Send + Sync + 'static,
+    {
+        self.insert(ResourceBindingWith::<R, Sink> {
+            update: Box::new(f),
+        })
+    }
+}
+
+pub trait DataBindingAppExt {
+    /// Add a system that updates the sinks of bindings made with
+    /// [`DataBindingExt::bind_to_with`] between `Source` and `Sink`.
+    ///
+    /// Sinks are updated when the source component changes and when the binding is created.
+    fn register_data_binding<Source, Sink>(&mut self) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>;
+
+    /// Add observers that keep the bindings made with [`DataBindingExt::bind_to_with`] between
+    /// `Source` and `Sink`.
+    ///
+    /// This is an alternative to [`DataBindingAppExt::register_data_binding`] where sinks are
+    /// initialized as soon as they are bound, only sources that changed are visited, and bindings
+    /// are released as soon as either end is despawned.
+    fn observe_data_binding<Source, Sink>(&mut self) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>;
+
+    /// Add a system that updates the sinks of bindings made with
+    /// [`DataBindingExt::bind_to_resource_with`] between `R` and `Sink`.
+    ///
+    /// Sinks are updated when the resource changes and when the binding is created.
+    fn register_resource_binding<R, Sink>(&mut self) -> &mut Self
+    where
+        R: Resource,
+        Sink: Component<Mutability = Mutable>;
+
+    /// Initialize the [`Aggregate`] resource `A` and add a system that keeps it up to date.
+    ///
+    /// Aggregates are computed before the sinks are updated, so resource bindings to `A` see the
+    /// value of the current frame.
+    fn init_aggregate<A: Aggregate>(&mut self) -> &mut Self;
+}
+
+impl DataBindingAppExt for App {
+    fn register_data_binding<Source, Sink>(&mut self) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>,
+    {
+        self.add_systems(
+            PostUpdate,
+            update_data_bindings_with::<Source, Sink>.in_set(DataBindingSet::Propagate),
+        )
+    }
+
+    fn observe_data_binding<Source, Sink>(&mut self) -> &mut Self
+    where
+        Source: Component,
+        Sink: Component<Mutability = Mutable>,
+    {
+        self.add_systems(
+            PostUpdate,
+            observer::propagate_changed_sources::<Source, Sink>.in_set(DataBindingSet::Propagate),
+        )
+        .add_observer(observer::initialize_sink::<Source, Sink>)
+        .add_observer(observer::unbind_sink::<Source, Sink>)
+        .add_observer(observer::release_sinks::<Source, Sink>)
+    }
+
+    fn register_resource_binding<R, Sink>(&mut self) -> &mut Self
+    where
+        R: Resource,
+        Sink: Component<Mutability = Mutable>,
+    {
+        self.add_systems(
+            PostUpdate,
+            update_resource_bindings_with::<R, Sink>
+                .run_if(resource_exists::<R>)
+                .in_set(DataBindingSet::Propagate),
+        )
+    }
+
+    fn init_aggregate<A: Aggregate>(&mut self) -> &mut Self {
+        self.init_resource::<A>().add_systems(
+            PostUpdate,
+            update_aggregate::<A>.in_set(DataBindingSet::Aggregate),
+        )
+    }
 }
 
 /// System parameter that provides a mechanism of updating entities with data bindings.
@@ -130,6 +312,63 @@ where
     }
 }
 
+fn update_data_bindings_with<Source, Sink>(
+    changed_query: Query<(Entity, &Source, &DataBindings), Changed<Source>>,
+    source_query: Query<&Source>,
+    added_query: Query<Entity, Added<DataBindingWith<Source, Sink>>>,
+    mut sink_query: Query<(&DataBindingWith<Source, Sink>, &mut Sink)>,
+    mut expired_events: EventWriter<DataBindingExpired>,
+) where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    for (source, source_data, bindings) in &changed_query {
+        for &sink in bindings {
+            match sink_query.get_mut(sink) {
+                Ok((binding, mut sink_data)) if binding.source == source => {
+                    (binding.update)(source_data, &mut sink_data);
+                }
+                Ok(_) => { /* Bound to the source with a different binding */ }
+                Err(QueryEntityError::EntityDoesNotExist(_)) => {
+                    expired_events.write(DataBindingExpired { source, sink });
+                }
+                Err(_) => { /* Entity exists but without the desired components */ }
+            }
+        }
+    }
+
+    for sink in &added_query {
+        let Ok((binding, mut sink_data)) = sink_query.get_mut(sink) else {
+            continue;
+        };
+        if changed_query.contains(binding.source) {
+            continue;
+        }
+        if let Ok(source_data) = source_query.get(binding.source) {
+            (binding.update)(source_data, &mut sink_data);
+        }
+    }
+}
+
+fn update_resource_bindings_with<R, Sink>(
+    resource: Res<R>,
+    mut sink_query: Query<(Ref<ResourceBindingWith<R, Sink>>, &mut Sink)>,
+) where
+    R: Resource,
+    Sink: Component<Mutability = Mutable>,
+{
+    let changed = resource.is_changed();
+    for (binding, mut sink_data) in &mut sink_query {
+        if changed || binding.is_added() {
+            (binding.update)(&resource, &mut sink_data);
+        }
+    }
+}
+
+fn update_aggregate<A: Aggregate>(query: Query<A::Data, A::Filter>, mut aggregate: ResMut<A>) {
+    aggregate.set_if_neq(A::aggregate(&query));
+}
+
 fn remove_expired_data_bindings(
     mut expired_events: EventReader<DataBindingExpired>,
     mut data_bindings_query: Query<&mut DataBindings>,
@@ -174,6 +413,35 @@ mod tests {
         });
     }
 
+    fn configure_with(mut commands: Commands) {
+        let temp1 = commands.spawn(Temperature(21)).id();
+        let temp2 = commands.spawn(Temperature(17)).id();
+        for temp in [temp1, temp1, temp2] {
+            commands.spawn(Display("--".to_string(), 0)).bind_to_with(
+                temp,
+                |temp: &Temperature, display: &mut Display| {
+                    display.0 = format!("{} °C", temp.0);
+                    display.1 += 1;
+                },
+            );
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Forecast(u16);
+
+    #[derive(Resource, PartialEq, Default, Debug)]
+    struct WarmSensors(usize);
+
+    impl Aggregate for WarmSensors {
+        type Data = &'static Temperature;
+        type Filter = ();
+
+        fn aggregate(query: &Query<&Temperature>) -> Self {
+            Self(query.iter().filter(|temp| temp.0 > 19).count())
+        }
+    }
+
     fn update_temperature(mut temperature_query: Query<&mut Temperature>) {
         for mut temp in &mut temperature_query {
             if temp.0 > 19 {
@@ -272,4 +540,229 @@ mod tests {
 
         assert_eq!(total_bindings, 2);
     }
+
+    #[test]
+    fn update_sink_with() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .register_data_binding::<Temperature, Display>()
+            .add_systems(Update, update_temperature);
+        assert!(app.world_mut().run_system_once(configure_with).is_ok());
+
+        let mut displays = || -> Vec<(String, u16)> {
+            app.update();
+            app.world_mut()
+                .query::<&Display>()
+                .iter(app.world())
+                .map(|display| (display.0.clone(), display.1))
+                .collect()
+        };
+
+        assert_eq!(
+            displays(),
+            [
+                ("20 °C".to_string(), 1),
+                ("20 °C".to_string(), 1),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            displays(),
+            [
+                ("19 °C".to_string(), 2),
+                ("19 °C".to_string(), 2),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            displays(),
+            [
+                ("19 °C".to_string(), 2),
+                ("19 °C".to_string(), 2),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn observe_source() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .observe_data_binding::<Temperature, Display>();
+        assert!(app.world_mut().run_system_once(configure_with).is_ok());
+
+        let displays = |app: &mut App| -> Vec<(String, u16)> {
+            app.world_mut()
+                .query::<&Display>()
+                .iter(app.world())
+                .map(|display| (display.0.clone(), display.1))
+                .collect()
+        };
+
+        assert_eq!(
+            displays(&mut app),
+            [
+                ("21 °C".to_string(), 1),
+                ("21 °C".to_string(), 1),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+
+        let temp = app
+            .world_mut()
+            .query_filtered::<Entity, With<Temperature>>()
+            .iter(app.world())
+            .next()
+            .unwrap();
+        app.world_mut().entity_mut(temp).insert(Temperature(25));
+        app.update();
+
+        assert_eq!(
+            displays(&mut app),
+            [
+                ("25 °C".to_string(), 2),
+                ("25 °C".to_string(), 2),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn observe_mutation() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .observe_data_binding::<Temperature, Display>();
+        assert!(app.world_mut().run_system_once(configure_with).is_ok());
+
+        let temp = app
+            .world_mut()
+            .query_filtered::<Entity, With<Temperature>>()
+            .iter(app.world())
+            .next()
+            .unwrap();
+        app.update();
+        app.world_mut().get_mut::<Temperature>(temp).unwrap().0 = 25;
+        app.update();
+
+        let displays: Vec<_> = app
+            .world_mut()
+            .query::<&Display>()
+            .iter(app.world())
+            .map(|display| (display.0.clone(), display.1))
+            .collect();
+        assert_eq!(
+            displays,
+            [
+                ("25 °C".to_string(), 2),
+                ("25 °C".to_string(), 2),
+                ("17 °C".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn observe_despawn() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .observe_data_binding::<Temperature, Display>();
+        assert!(app.world_mut().run_system_once(configure_with).is_ok());
+
+        let total_bindings = |app: &mut App| -> (usize, usize) {
+            let sources = app
+                .world_mut()
+                .query::<&DataBindings>()
+                .iter(app.world())
+                .map(|bindings| bindings.0.len())
+                .sum();
+            let sinks = app
+                .world_mut()
+                .query::<&DataBindingWith<Temperature, Display>>()
+                .iter(app.world())
+                .count();
+            (sources, sinks)
+        };
+        assert_eq!(total_bindings(&mut app), (3, 3));
+
+        let display = app
+            .world_mut()
+            .query_filtered::<Entity, With<Display>>()
+            .iter(app.world())
+            .next()
+            .unwrap();
+        app.world_mut().despawn(display);
+        assert_eq!(total_bindings(&mut app), (2, 2));
+
+        let temp = app
+            .world_mut()
+            .query_filtered::<Entity, With<Temperature>>()
+            .iter(app.world())
+            .next()
+            .unwrap();
+        app.world_mut().despawn(temp);
+        app.world_mut().flush();
+        assert_eq!(total_bindings(&mut app), (1, 1));
+    }
+
+    #[test]
+    fn update_resource_sink_with() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .insert_resource(Forecast(21))
+            .register_resource_binding::<Forecast, Display>();
+        app.world_mut()
+            .commands()
+            .spawn(Display("--".to_string(), 0))
+            .bind_to_resource_with(|forecast: &Forecast, display: &mut Display| {
+                display.0 = format!("{} °C", forecast.0);
+                display.1 += 1;
+            });
+
+        let display = |app: &mut App| -> (String, u16) {
+            app.update();
+            let display = app
+                .world_mut()
+                .query::<&Display>()
+                .single(app.world())
+                .unwrap();
+            (display.0.clone(), display.1)
+        };
+
+        assert_eq!(display(&mut app), ("21 °C".to_string(), 1));
+        assert_eq!(display(&mut app), ("21 °C".to_string(), 1));
+        app.world_mut().resource_mut::<Forecast>().0 = 15;
+        assert_eq!(display(&mut app), ("15 °C".to_string(), 2));
+    }
+
+    #[test]
+    fn update_aggregate_sink() {
+        let mut app = App::new();
+        app.add_plugins(DataBindingPlugin)
+            .init_aggregate::<WarmSensors>()
+            .register_resource_binding::<WarmSensors, Display>()
+            .add_systems(Update, update_temperature);
+        assert!(app.world_mut().run_system_once(configure).is_ok());
+        app.world_mut()
+            .commands()
+            .spawn(Display("--".to_string(), 0))
+            .bind_to_resource_with(|warm: &WarmSensors, display: &mut Display| {
+                display.0 = format!("{} warm", warm.0);
+                display.1 += 1;
+            });
+
+        let display = |app: &mut App| -> (String, u16) {
+            app.update();
+            let mut query = app
+                .world_mut()
+                .query_filtered::<&Display, With<ResourceBindingWith<WarmSensors, Display>>>();
+            let display = query.single(app.world()).unwrap();
+            (display.0.clone(), display.1)
+        };
+
+        // 21 -> 20
+        assert_eq!(display(&mut app), ("1 warm".to_string(), 1));
+        // 20 -> 19
+        assert_eq!(display(&mut app), ("0 warm".to_string(), 2));
+        // 19 -> 19
+        assert_eq!(display(&mut app), ("0 warm".to_string(), 2));
+    }
 }
@@ -0,0 +1,95 @@
+//! Propagation of bound data with observers.
+//!
+//! Sinks are initialized by an observer as soon as the binding is created, and afterwards only
+//! visited when their source component has changed, either by insertion or in place through
+//! [`Mut`].
+//!
+//! Bindings are also released by observers when either the source or the sink is despawned, which
+//! makes the [`DataBindingExpired`](super::DataBindingExpired) event unnecessary.
+use super::{DataBindingWith, DataBindings};
+use bevy_ecs::{component::Mutable, prelude::*, system::SystemChangeTick};
+
+/// Update the sinks bound to the sources whose component changed since the last run.
+pub(super) fn propagate_changed_sources<Source, Sink>(
+    source_query: Query<(Entity, Ref<Source>, &DataBindings), Changed<Source>>,
+    mut sink_query: Query<(Ref<DataBindingWith<Source, Sink>>, &mut Sink)>,
+    ticks: SystemChangeTick,
+) where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    for (source, source_data, bindings) in &source_query {
+        let mut sinks = sink_query.iter_many_mut(bindings);
+        while let Some((binding, mut sink_data)) = sinks.fetch_next() {
+            // Sinks bound after the last change were already initialized with the current value.
+            if binding.source == source
+                && source_data
+                    .last_changed()
+                    .is_newer_than(binding.added(), ticks.this_run())
+            {
+                (binding.update)(&source_data, &mut sink_data);
+            }
+        }
+    }
+}
+
+/// Update a sink with the current value of the source when the binding is created.
+pub(super) fn initialize_sink<Source, Sink>(
+    trigger: Trigger<OnInsert, DataBindingWith<Source, Sink>>,
+    source_query: Query<&Source>,
+    mut sink_query: Query<(&DataBindingWith<Source, Sink>, &mut Sink)>,
+) where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    let Ok((binding, mut sink_data)) = sink_query.get_mut(trigger.target()) else {
+        return;
+    };
+    if let Ok(source_data) = source_query.get(binding.source) {
+        (binding.update)(source_data, &mut sink_data);
+    }
+}
+
+/// Remove the sink from the bindings of the source when the binding is removed from the sink.
+pub(super) fn unbind_sink<Source, Sink>(
+    trigger: Trigger<OnRemove, DataBindingWith<Source, Sink>>,
+    binding_query: Query<&DataBindingWith<Source, Sink>>,
+    mut data_bindings_query: Query<&mut DataBindings>,
+) where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    let sink = trigger.target();
+    let Ok(binding) = binding_query.get(sink) else {
+        return;
+    };
+    if let Ok(mut data_bindings) = data_bindings_query.get_mut(binding.source) {
+        data_bindings.unbind(sink);
+    }
+}
+
+/// Remove the bindings from the sinks when the bindings are removed from the source.
+pub(super) fn release_sinks<Source, Sink>(
+    trigger: Trigger<OnRemove, DataBindings>,
+    mut commands: Commands,
+    data_bindings_query: Query<&DataBindings>,
+    binding_query: Query<&DataBindingWith<Source, Sink>>,
+) where
+    Source: Component,
+    Sink: Component<Mutability = Mutable>,
+{
+    let source = trigger.target();
+    let Ok(data_bindings) = data_bindings_query.get(source) else {
+        return;
+    };
+    for &sink in data_bindings {
+        if binding_query
+            .get(sink)
+            .is_ok_and(|binding| binding.source == source)
+        {
+            commands
+                .entity(sink)
+                .try_remove::<DataBindingWith<Source, Sink>>();
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use super::{HexCoord, MapEvent, MapPresence, PresenceLayer};
+use super::{
+    EnteredZone, HexCoord, LeftZone, MapCommandFailed, MapError, MapEvent, MapPresence, OnMap,
+    PresenceArrived, PresenceDeparted, PresenceLayer, ZoneLayer,
+};
 use bevy_ecs::{
     prelude::*,
     system::{Command, EntityCommands},
@@ -13,7 +16,7 @@ struct AddMapPresence {
 
 struct MoveMapPresence {
     pub map: Entity,
-    pub presence: Entity,
+    pub presence: SmallVec<[Entity; 8]>,
     pub position: HexCoord,
 }
 
@@ -27,14 +30,24 @@ pub struct PresenceBuilder<'w, 's> {
     add_map_presence: AddMapPresence,
 }
 
+/// Commands that place presences on the map of the entity.
+///
+/// Commands that cannot be applied, e.g because the position is outside of the map, leave the
+/// presences as they were and send a [`MapCommandFailed`] event.
 pub trait MapCommandsExt {
+    /// Add a presence to the map, a presence that is already on the map is moved and a presence
+    /// on another map is transferred.
     fn add_presence(&mut self, presence: Entity, position: HexCoord) -> &mut Self;
     fn move_presence(&mut self, presence: Entity, position: HexCoord) -> &mut Self;
+    /// Move several presences on the map together, either all of them are moved or none are.
+    fn move_presences(&mut self, presences: &[Entity], position: HexCoord) -> &mut Self;
     fn with_presence(
         &mut self,
         position: HexCoord,
         f: impl FnOnce(&mut PresenceBuilder),
     ) -> &mut Self;
+    /// Move a presence from the map it is currently on to this map.
+    fn transfer_presence(&mut self, presence: Entity, position: HexCoord) -> &mut Self;
     fn despawn_presence(&mut self, presence: Entity) -> &mut Self;
 }
 
@@ -58,10 +71,14 @@ impl MapCommandsExt for EntityCommands<'_> {
     }
 
     fn move_presence(&mut self, presence: Entity, position: HexCoord) -> &mut Self {
+        self.move_presences(&[presence], position)
+    }
+
+    fn move_presences(&mut self, presences: &[Entity], position: HexCoord) -> &mut Self {
         let map = self.id();
         self.commands().queue(MoveMapPresence {
             map,
-            presence,
+            presence: SmallVec::from_slice(presences),
             position,
         });
         self
@@ -87,6 +104,10 @@ impl MapCommandsExt for EntityCommands<'_> {
         self
     }
 
+    fn transfer_presence(&mut self, presence: Entity, position: HexCoord) -> &mut Self {
+        self.add_presence(presence, position)
+    }
+
     fn despawn_presence(&mut self, presence: Entity) -> &mut Self {
         let map = self.id();
         self.commands().queue(DespawnPresence { map, presence });
@@ -94,79 +115,534 @@ impl MapCommandsExt for EntityCommands<'_> {
     }
 }
 
-impl Command for MoveMapPresence {
-    fn apply(self, world: &mut World) {
-        if let Some(current_position) = world
-            .entity(self.presence)
-            .get::<MapPresence>()
-            .map(|presence| presence.position)
-        {
-            if let Some(mut map) = world.entity_mut(self.map).get_mut::<PresenceLayer>() {
-                map.move_presence(self.presence, current_position, self.position);
-            }
-        }
+/// The map and position of a presence, if it is on a map.
+fn locate(world: &World, presence: Entity) -> Result<Option<(Entity, HexCoord)>, MapError> {
+    let entity = world
+        .get_entity(presence)
+        .map_err(|_| MapError::MissingPresence)?;
+    Ok(entity
+        .get::<OnMap>()
+        .zip(entity.get::<MapPresence>())
+        .map(|(&OnMap(map), presence)| (map, presence.position)))
+}
 
-        if let Some(mut presence) = world.entity_mut(self.presence).get_mut::<MapPresence>() {
-            presence.position = self.position;
-        }
+fn check_position(world: &World, map: Entity, position: HexCoord) -> Result<(), MapError> {
+    let presence_layer = world
+        .get::<PresenceLayer>(map)
+        .ok_or(MapError::MissingMap)?;
+    if presence_layer.contains(position) {
+        Ok(())
+    } else {
+        Err(MapError::OutOfBounds(position))
+    }
+}
 
-        if let Some(mut events) = world.get_resource_mut::<Events<MapEvent>>() {
-            events.send(MapEvent::PresenceMoved {
-                map: self.map,
-                presence: self.presence,
-                position: self.position,
-            });
-        }
+fn send_event(world: &mut World, event: MapEvent) {
+    if let Some(mut events) = world.get_resource_mut::<Events<MapEvent>>() {
+        events.send(event);
+    }
+}
+
+fn send_error(world: &mut World, map: Entity, presence: Entity, error: MapError) {
+    if let Some(mut events) = world.get_resource_mut::<Events<MapCommandFailed>>() {
+        events.send(MapCommandFailed {
+            map,
+            presence,
+            error,
+        });
+    }
+}
+
+fn zone_at(world: &World, map: Entity, position: HexCoord) -> Option<Entity> {
+    world.get::<ZoneLayer>(map)?.get(position).copied()
+}
+
+fn trigger_left_zone(world: &mut World, map: Entity, presence: Entity, position: HexCoord) {
+    if let Some(zone) = zone_at(world, map, position) {
+        world.trigger_targets(LeftZone { zone, position }, presence);
+        world.trigger_targets(PresenceDeparted { presence }, zone);
+    }
+}
+
+fn trigger_entered_zone(world: &mut World, map: Entity, presence: Entity, position: HexCoord) {
+    if let Some(zone) = zone_at(world, map, position) {
+        world.trigger_targets(EnteredZone { zone, position }, presence);
+        world.trigger_targets(PresenceArrived { presence }, zone);
+    }
+}
+
+fn place(world: &mut World, map: Entity, presence: Entity, position: HexCoord) {
+    if let Some(mut presence_layer) = world.get_mut::<PresenceLayer>(map) {
+        presence_layer.add_presence(position, presence);
+    }
+    world
+        .entity_mut(presence)
+        .insert((MapPresence { position }, OnMap(map)));
+    send_event(
+        world,
+        MapEvent::PresenceAdded {
+            map,
+            presence,
+            position,
+        },
+    );
+    trigger_entered_zone(world, map, presence, position);
+}
+
+fn relocate(
+    world: &mut World,
+    map: Entity,
+    presence: Entity,
+    origin: HexCoord,
+    position: HexCoord,
+) {
+    if let Some(mut presence_layer) = world.get_mut::<PresenceLayer>(map) {
+        presence_layer.move_presence(presence, origin, position);
+    }
+    if let Some(mut map_presence) = world.get_mut::<MapPresence>(presence) {
+        map_presence.position = position;
+    }
+    send_event(
+        world,
+        MapEvent::PresenceMoved {
+            map,
+            presence,
+            position,
+        },
+    );
+    if origin != position {
+        trigger_left_zone(world, map, presence, origin);
+        trigger_entered_zone(world, map, presence, position);
     }
 }
 
+fn remove(world: &mut World, map: Entity, presence: Entity, position: HexCoord) {
+    if let Some(mut presence_layer) = world.get_mut::<PresenceLayer>(map) {
+        presence_layer.remove_presence(position, presence);
+    }
+    send_event(
+        world,
+        MapEvent::PresenceRemoved {
+            map,
+            presence,
+            position,
+        },
+    );
+    trigger_left_zone(world, map, presence, position);
+}
+
 impl Command for AddMapPresence {
     fn apply(self, world: &mut World) {
         for presence in self.presence {
-            // TODO: How to handle the case where presence is already on map? Convert into move?
-            if let Some(mut map) = world.entity_mut(self.map).get_mut::<PresenceLayer>() {
-                map.add_presence(self.position, presence);
+            let location = check_position(world, self.map, self.position)
+                .and_then(|()| locate(world, presence));
+            match location {
+                Ok(Some((map, origin))) if map == self.map => {
+                    relocate(world, map, presence, origin, self.position);
+                }
+                Ok(Some((map, origin))) => {
+                    remove(world, map, presence, origin);
+                    place(world, self.map, presence, self.position);
+                }
+                Ok(None) => place(world, self.map, presence, self.position),
+                Err(error) => send_error(world, self.map, presence, error),
             }
+        }
+    }
+}
 
-            let mut presence_entity = world.entity_mut(presence);
-            if let Some(mut presence) = presence_entity.get_mut::<MapPresence>() {
-                presence.position = self.position;
-            } else {
-                presence_entity.insert(MapPresence {
-                    position: self.position,
-                });
-            }
+impl Command for MoveMapPresence {
+    fn apply(self, world: &mut World) {
+        let origins = self
+            .presence
+            .iter()
+            .map(|&presence| {
+                check_position(world, self.map, self.position)
+                    .and_then(|()| locate(world, presence))
+                    .and_then(|location| match location {
+                        Some((map, origin)) if map == self.map => Ok((presence, origin)),
+                        _ => Err(MapError::NotOnMap),
+                    })
+                    .map_err(|error| (presence, error))
+            })
+            .collect::<Result<SmallVec<[_; 8]>, _>>();
 
-            if let Some(mut events) = world.get_resource_mut::<Events<MapEvent>>() {
-                events.send(MapEvent::PresenceAdded {
-                    map: self.map,
-                    presence,
-                    position: self.position,
-                });
+        match origins {
+            Ok(origins) => {
+                for (presence, origin) in origins {
+                    relocate(world, self.map, presence, origin, self.position);
+                }
             }
+            Err((presence, error)) => send_error(world, self.map, presence, error),
         }
     }
 }
 
 impl Command for DespawnPresence {
     fn apply(self, world: &mut World) {
-        if let Some(position) = world
-            .entity(self.presence)
-            .get::<MapPresence>()
-            .map(|presence| presence.position)
-        {
-            if let Some(mut map) = world.entity_mut(self.map).get_mut::<PresenceLayer>() {
-                map.remove_presence(position, self.presence);
-            }
-            if let Some(mut events) = world.get_resource_mut::<Events<MapEvent>>() {
-                events.send(MapEvent::PresenceRemoved {
-                    map: self.map,
-                    presence: self.presence,
-                    position,
-                });
+        match locate(world, self.presence) {
+            Ok(Some((map, position))) if map == self.map => {
+                remove(world, map, self.presence, position);
+                world.despawn(self.presence);
             }
+            Ok(_) => send_error(world, self.map, self.presence, MapError::NotOnMap),
+            Err(error) => send_error(world, self.map, self.presence, error),
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SquareGridLayout;
+    use expl_hexgrid::GridLayout;
+
+    #[derive(Resource, Default)]
+    struct Triggered(Vec<(Entity, &'static str, Entity)>);
+
+    fn setup() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Events<MapEvent>>();
+        world.init_resource::<Events<MapCommandFailed>>();
+        world.init_resource::<Triggered>();
+        world.add_observer(
+            |trigger: Trigger<EnteredZone>, mut triggered: ResMut<Triggered>| {
+                triggered
+                    .0
+                    .push((trigger.target(), "entered", trigger.zone));
+            },
+        );
+        world.add_observer(
+            |trigger: Trigger<LeftZone>, mut triggered: ResMut<Triggered>| {
+                triggered.0.push((trigger.target(), "left", trigger.zone));
+            },
+        );
+        let map = spawn_map(&mut world);
+        (world, map)
+    }
+
+    fn spawn_map(world: &mut World) -> Entity {
+        let layout = SquareGridLayout {
+            width: 3,
+            height: 3,
+        };
+        let zones = layout.iter().map(|_| world.spawn_empty().id()).collect();
+        world
+            .spawn((PresenceLayer::new(layout), ZoneLayer::new(layout, zones)))
+            .id()
+    }
+
+    fn map_events(world: &mut World) -> Vec<MapEvent> {
+        world.resource_mut::<Events<MapEvent>>().drain().collect()
+    }
+
+    fn failures(world: &mut World) -> Vec<MapCommandFailed> {
+        world
+            .resource_mut::<Events<MapCommandFailed>>()
+            .drain()
+            .collect()
+    }
+
+    fn presence_at(world: &World, map: Entity, position: HexCoord) -> Vec<Entity> {
+        world
+            .get::<PresenceLayer>(map)
+            .unwrap()
+            .presence(position)
+            .copied()
+            .collect()
+    }
+
+    fn add(world: &mut World, map: Entity, presence: Entity, position: HexCoord) {
+        world
+            .commands()
+            .entity(map)
+            .add_presence(presence, position);
+        world.flush();
+    }
+
+    #[test]
+    fn add_presence() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let position = HexCoord::new(1, 1);
+        add(&mut world, map, presence, position);
+
+        assert_eq!(presence_at(&world, map, position), vec![presence]);
+        assert_eq!(
+            world.get::<MapPresence>(presence).unwrap().position,
+            position
+        );
+        assert_eq!(world.get::<OnMap>(presence).unwrap().0, map);
+        assert_eq!(
+            map_events(&mut world),
+            vec![MapEvent::PresenceAdded {
+                map,
+                presence,
+                position
+            }]
+        );
+        assert!(failures(&mut world).is_empty());
+    }
+
+    #[test]
+    fn add_presence_twice_moves() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let origin = HexCoord::new(0, 0);
+        let position = HexCoord::new(1, 1);
+        add(&mut world, map, presence, origin);
+        map_events(&mut world);
+        add(&mut world, map, presence, position);
+
+        assert!(presence_at(&world, map, origin).is_empty());
+        assert_eq!(presence_at(&world, map, position), vec![presence]);
+        assert_eq!(
+            map_events(&mut world),
+            vec![MapEvent::PresenceMoved {
+                map,
+                presence,
+                position
+            }]
+        );
+    }
+
+    #[test]
+    fn add_presence_out_of_bounds() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let position = HexCoord::new(5, 5);
+        add(&mut world, map, presence, position);
+
+        assert!(world.get::<MapPresence>(presence).is_none());
+        assert!(world.get::<OnMap>(presence).is_none());
+        assert!(map_events(&mut world).is_empty());
+        assert_eq!(
+            failures(&mut world),
+            vec![MapCommandFailed {
+                map,
+                presence,
+                error: MapError::OutOfBounds(position)
+            }]
+        );
+    }
+
+    #[test]
+    fn add_missing_presence() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        world.despawn(presence);
+        add(&mut world, map, presence, HexCoord::ZERO);
+
+        assert!(presence_at(&world, map, HexCoord::ZERO).is_empty());
+        assert_eq!(failures(&mut world)[0].error, MapError::MissingPresence);
+    }
+
+    #[test]
+    fn add_presence_to_other_map_transfers() {
+        let (mut world, map) = setup();
+        let other_map = spawn_map(&mut world);
+        let presence = world.spawn_empty().id();
+        let position = HexCoord::new(1, 1);
+        add(&mut world, map, presence, position);
+        map_events(&mut world);
+        add(&mut world, other_map, presence, position);
+
+        assert!(presence_at(&world, map, position).is_empty());
+        assert_eq!(presence_at(&world, other_map, position), vec![presence]);
+        assert_eq!(world.get::<OnMap>(presence).unwrap().0, other_map);
+        assert_eq!(
+            map_events(&mut world),
+            vec![
+                MapEvent::PresenceRemoved {
+                    map,
+                    presence,
+                    position
+                },
+                MapEvent::PresenceAdded {
+                    map: other_map,
+                    presence,
+                    position
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn move_presence_out_of_bounds() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let origin = HexCoord::new(1, 1);
+        add(&mut world, map, presence, origin);
+        map_events(&mut world);
+        let position = HexCoord::new(-4, 0);
+        world
+            .commands()
+            .entity(map)
+            .move_presence(presence, position);
+        world.flush();
+
+        assert_eq!(presence_at(&world, map, origin), vec![presence]);
+        assert_eq!(world.get::<MapPresence>(presence).unwrap().position, origin);
+        assert!(map_events(&mut world).is_empty());
+        assert_eq!(
+            failures(&mut world)[0].error,
+            MapError::OutOfBounds(position)
+        );
+    }
+
+    #[test]
+    fn move_presence_not_on_map() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        world
+            .commands()
+            .entity(map)
+            .move_presence(presence, HexCoord::ZERO);
+        world.flush();
+
+        assert!(world.get::<MapPresence>(presence).is_none());
+        assert!(map_events(&mut world).is_empty());
+        assert_eq!(failures(&mut world)[0].error, MapError::NotOnMap);
+    }
+
+    #[test]
+    fn move_presences_is_atomic() {
+        let (mut world, map) = setup();
+        let other_map = spawn_map(&mut world);
+        let origin = HexCoord::new(0, 1);
+        let first = world.spawn_empty().id();
+        let second = world.spawn_empty().id();
+        add(&mut world, map, first, origin);
+        add(&mut world, other_map, second, origin);
+        map_events(&mut world);
+        world
+            .commands()
+            .entity(map)
+            .move_presences(&[first, second], HexCoord::new(1, 1));
+        world.flush();
+
+        assert_eq!(presence_at(&world, map, origin), vec![first]);
+        assert_eq!(presence_at(&world, other_map, origin), vec![second]);
+        assert!(map_events(&mut world).is_empty());
+        assert_eq!(
+            failures(&mut world),
+            vec![MapCommandFailed {
+                map,
+                presence: second,
+                error: MapError::NotOnMap
+            }]
+        );
+    }
+
+    #[test]
+    fn move_presences() {
+        let (mut world, map) = setup();
+        let first = world.spawn_empty().id();
+        let second = world.spawn_empty().id();
+        add(&mut world, map, first, HexCoord::new(0, 0));
+        add(&mut world, map, second, HexCoord::new(0, 1));
+        map_events(&mut world);
+        let position = HexCoord::new(1, 1);
+        world
+            .commands()
+            .entity(map)
+            .move_presences(&[first, second], position);
+        world.flush();
+
+        let mut presence = presence_at(&world, map, position);
+        presence.sort();
+        assert_eq!(presence, vec![first, second]);
+        assert_eq!(map_events(&mut world).len(), 2);
+        assert!(failures(&mut world).is_empty());
+    }
+
+    #[test]
+    fn despawn_presence() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let position = HexCoord::new(2, 0);
+        add(&mut world, map, presence, position);
+        map_events(&mut world);
+        world.commands().entity(map).despawn_presence(presence);
+        world.flush();
+
+        assert!(world.get_entity(presence).is_err());
+        assert!(presence_at(&world, map, position).is_empty());
+        assert_eq!(
+            map_events(&mut world),
+            vec![MapEvent::PresenceRemoved {
+                map,
+                presence,
+                position
+            }]
+        );
+    }
+
+    #[test]
+    fn despawn_presence_not_on_map() {
+        let (mut world, map) = setup();
+        let other_map = spawn_map(&mut world);
+        let presence = world.spawn_empty().id();
+        add(&mut world, other_map, presence, HexCoord::ZERO);
+        map_events(&mut world);
+        world.commands().entity(map).despawn_presence(presence);
+        world.flush();
+
+        assert!(world.get_entity(presence).is_ok());
+        assert_eq!(
+            presence_at(&world, other_map, HexCoord::ZERO),
+            vec![presence]
+        );
+        assert!(map_events(&mut world).is_empty());
+        assert_eq!(failures(&mut world)[0].error, MapError::NotOnMap);
+    }
+
+    #[test]
+    fn zone_triggers() {
+        let (mut world, map) = setup();
+        let presence = world.spawn_empty().id();
+        let origin = HexCoord::new(0, 0);
+        let position = HexCoord::new(1, 0);
+        let zone_layer = world.get::<ZoneLayer>(map).unwrap();
+        let origin_zone = *zone_layer.get(origin).unwrap();
+        let zone = *zone_layer.get(position).unwrap();
+        add(&mut world, map, presence, origin);
+        add(&mut world, map, presence, origin);
+        world
+            .commands()
+            .entity(map)
+            .move_presence(presence, position);
+        world.flush();
+
+        assert_eq!(
+            world.resource::<Triggered>().0,
+            vec![
+                (presence, "entered", origin_zone),
+                (presence, "left", origin_zone),
+                (presence, "entered", zone),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_presence() {
+        let (mut world, map) = setup();
+        let position = HexCoord::new(1, 1);
+        let mut spawned = vec![];
+        world
+            .commands()
+            .entity(map)
+            .with_presence(position, |builder| {
+                spawned.push(builder.spawn(()).id());
+                spawned.push(builder.spawn(()).id());
+            });
+        world.flush();
 
-        world.despawn(self.presence);
+        let mut presence = presence_at(&world, map, position);
+        presence.sort();
+        assert_eq!(presence, spawned);
+        assert!(spawned
+            .iter()
+            .all(|&e| world.get::<OnMap>(e).unwrap().0 == map));
     }
 }
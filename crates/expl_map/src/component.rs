@@ -1,28 +1,195 @@
 use bevy_derive::Deref;
 use bevy_ecs::prelude::*;
 use bevy_reflect::Reflect;
-use expl_hexgrid::{layout::SquareGridLayout, Grid, HexCoord};
+use expl_hexgrid::{layout::SquareGridLayout, spiral, Grid, HexCoord};
 use std::collections::hash_set::HashSet;
 
 #[derive(Component, Reflect, Deref, Default)]
 #[reflect(Component)]
 pub struct MapLayout(pub SquareGridLayout);
 
-#[derive(Component, Reflect, Copy, Clone, Default, Debug)]
+/// Fog as seen by the [`ViewingFaction`].
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
 #[reflect(Component)]
 pub struct Fog {
     pub visible: bool,
     pub explored: bool,
 }
 
+/// Side that a presence acts for, e.g the player or the enemies.
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Faction(u8);
+
+impl Faction {
+    pub const PLAYER: Faction = Faction(0);
+    pub const ENEMY: Faction = Faction(1);
+
+    /// The faction with index `id`, if it fits in a [`FactionSet`].
+    pub const fn new(id: u8) -> Option<Self> {
+        if (id as u32) < FactionSet::CAPACITY {
+            Some(Faction(id))
+        } else {
+            None
+        }
+    }
+
+    pub fn id(&self) -> u8 {
+        self.0
+    }
+
+    /// The bit of the faction in a [`FactionSet`], none for factions that were created out of
+    /// range, e.g by reflection.
+    fn bit(&self) -> Option<u32> {
+        1u32.checked_shl(self.0.into())
+    }
+}
+
+/// Set of factions, there can be at most [`FactionSet::CAPACITY`] factions.
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct FactionSet(u32);
+
+impl FactionSet {
+    pub const CAPACITY: u32 = u32::BITS;
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, faction: Faction) -> bool {
+        faction.bit().is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    /// Add `faction` to the set, factions that are out of range are never part of a set.
+    pub fn insert(&mut self, faction: Faction) {
+        if let Some(bit) = faction.bit() {
+            self.0 |= bit;
+        }
+    }
+
+    pub fn union(&self, other: FactionSet) -> FactionSet {
+        FactionSet(self.0 | other.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Faction> + '_ {
+        (0..Self::CAPACITY as u8)
+            .map(Faction)
+            .filter(|&faction| self.contains(faction))
+    }
+}
+
+impl FromIterator<Faction> for FactionSet {
+    fn from_iter<T: IntoIterator<Item = Faction>>(iter: T) -> Self {
+        let mut set = FactionSet::default();
+        for faction in iter {
+            set.insert(faction);
+        }
+        set
+    }
+}
+
+/// Fog of a zone for every faction.
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+#[reflect(Component)]
+pub struct FactionFog {
+    pub visible: FactionSet,
+    pub explored: FactionSet,
+}
+
+impl FactionFog {
+    pub fn is_visible_to(&self, faction: Faction) -> bool {
+        self.visible.contains(faction)
+    }
+
+    pub fn is_explored_by(&self, faction: Faction) -> bool {
+        self.explored.contains(faction)
+    }
+
+    /// The fog as seen by `faction`.
+    pub fn fog(&self, faction: Faction) -> Fog {
+        Fog {
+            visible: self.is_visible_to(faction),
+            explored: self.is_explored_by(faction),
+        }
+    }
+}
+
+/// Presence that reveals the zones within its [`ViewRadius`] to its [`RevealerFaction`].
 #[derive(Component, Reflect, Copy, Clone, Default, Debug)]
 #[reflect(Component)]
 pub struct FogRevealer;
 
+/// Faction that a [`FogRevealer`] reveals zones to, revealers without one reveal to
+/// [`Faction::PLAYER`] as they did before there were factions.
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Default, Debug, Deref)]
+#[reflect(Component)]
+pub struct RevealerFaction(pub Faction);
+
+/// Turn counter used to record when presences were seen, kept up to date by the game.
+#[derive(Resource, Reflect, Copy, Clone, PartialEq, Eq, Default, Debug, Deref)]
+#[reflect(Resource)]
+pub struct MapTurn(pub u32);
+
+/// Presence as it was when it was last seen by a faction.
+#[derive(Clone, Debug)]
+pub struct Sighting<T> {
+    pub presence: Entity,
+    pub data: T,
+    pub turn: u32,
+}
+
+/// What each faction last saw of the presences on a zone.
+///
+/// A faction's sightings are replaced whenever the zone is visible to it, so when the zone is
+/// fogged they describe the presences that were there the last time it was seen, which may have
+/// moved or been despawned since.
+#[derive(Component, Debug)]
+pub struct LastSeen<T: Component + Clone> {
+    sightings: Vec<(Faction, Sighting<T>)>,
+}
+
+impl<T: Component + Clone> Default for LastSeen<T> {
+    fn default() -> Self {
+        Self {
+            sightings: Vec::new(),
+        }
+    }
+}
+
+impl<T: Component + Clone> LastSeen<T> {
+    pub fn seen_by(&self, faction: Faction) -> impl Iterator<Item = &Sighting<T>> {
+        self.sightings
+            .iter()
+            .filter(move |(seen_by, _)| *seen_by == faction)
+            .map(|(_, sighting)| sighting)
+    }
+
+    pub fn replace(&mut self, faction: Faction, sightings: impl IntoIterator<Item = Sighting<T>>) {
+        self.sightings.retain(|(seen_by, _)| *seen_by != faction);
+        self.sightings
+            .extend(sightings.into_iter().map(|sighting| (faction, sighting)));
+    }
+}
+
+/// The faction whose view of the map is shown, e.g the player whose turn it is in hot-seat play.
+#[derive(Resource, Reflect, Copy, Clone, PartialEq, Eq, Default, Debug, Deref)]
+#[reflect(Resource)]
+pub struct ViewingFaction(pub Faction);
+
 #[derive(Component, Reflect, Debug, Default, Deref)]
 #[reflect(Component)]
 pub struct MapPosition(pub HexCoord);
 
+/// The map that a zone or presence belongs to.
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Debug, Deref)]
+#[reflect(Component)]
+#[relationship(relationship_target = MapMembers)]
+pub struct OnMap(pub Entity);
+
+/// Zones and presences that belong to the map, see [`OnMap`].
+#[derive(Component, Debug, Default, Deref)]
+#[relationship_target(relationship = OnMap)]
+pub struct MapMembers(Vec<Entity>);
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct MapPresence {
@@ -63,6 +230,31 @@ impl PresenceLayer {
             .map_or_else(|| self.void.iter(), |presence| presence.iter())
     }
 
+    /// Whether `position` is on the map.
+    pub fn contains(&self, position: HexCoord) -> bool {
+        self.presence.get(position).is_some()
+    }
+
+    /// Number of presences at `position`.
+    pub fn count(&self, position: HexCoord) -> usize {
+        self.presence.get(position).map_or(0, HashSet::len)
+    }
+
+    /// Presences within `radius` of `center`, ordered by their distance to `center`.
+    ///
+    /// The radius is limited to the size of the map, so `u32::MAX` covers the whole map.
+    pub fn presence_within(
+        &self,
+        center: HexCoord,
+        radius: u32,
+    ) -> impl Iterator<Item = (HexCoord, Entity)> + '_ {
+        let layout = self.presence.layout;
+        let radius = radius.min((layout.width + layout.height) as u32);
+        spiral(center)
+            .take_while(move |position| position.distance(center) <= radius)
+            .flat_map(move |position| self.presence(position).map(move |&e| (position, e)))
+    }
+
     pub fn add_presence(&mut self, position: HexCoord, entity: Entity) {
         if let Some(presence) = self.presence.get_mut(position) {
             presence.insert(entity);
@@ -109,3 +301,79 @@ impl ZoneLayer {
         self.tiles.get(position)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faction_set() {
+        let mut set = FactionSet::default();
+        set.insert(Faction::ENEMY);
+        assert!(set.contains(Faction::ENEMY));
+        assert!(!set.contains(Faction::PLAYER));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [Faction::ENEMY]);
+
+        let last = Faction::new(31).unwrap();
+        set.insert(last);
+        assert!(set.contains(last));
+        assert_eq!(Faction::new(32), None);
+
+        let out_of_range = Faction(40);
+        set.insert(out_of_range);
+        assert!(!set.contains(out_of_range));
+        assert_eq!(set.iter().count(), 2);
+    }
+
+    #[test]
+    fn presence_count() {
+        let mut layer = PresenceLayer::new(SquareGridLayout {
+            width: 3,
+            height: 3,
+        });
+        let position = HexCoord::new(1, 1);
+        layer.add_presence(position, Entity::from_raw(1));
+        layer.add_presence(position, Entity::from_raw(2));
+        assert_eq!(layer.count(position), 2);
+        assert_eq!(layer.count(HexCoord::ZERO), 0);
+        assert_eq!(layer.count(HexCoord::new(5, 5)), 0);
+
+        layer.remove_presence(position, Entity::from_raw(1));
+        assert_eq!(layer.count(position), 1);
+    }
+
+    #[test]
+    fn presence_within() {
+        let mut layer = PresenceLayer::new(SquareGridLayout {
+            width: 3,
+            height: 3,
+        });
+        let (a, b, c) = (
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        );
+        layer.add_presence(HexCoord::ZERO, a);
+        layer.add_presence(HexCoord::new(1, 1), b);
+        layer.add_presence(HexCoord::new(0, 1), c);
+
+        assert_eq!(
+            layer.presence_within(HexCoord::ZERO, 0).collect::<Vec<_>>(),
+            [(HexCoord::ZERO, a)]
+        );
+        assert_eq!(
+            layer.presence_within(HexCoord::ZERO, 1).collect::<Vec<_>>(),
+            [(HexCoord::ZERO, a), (HexCoord::new(0, 1), c)]
+        );
+        assert_eq!(
+            layer
+                .presence_within(HexCoord::ZERO, u32::MAX)
+                .collect::<Vec<_>>(),
+            [
+                (HexCoord::ZERO, a),
+                (HexCoord::new(0, 1), c),
+                (HexCoord::new(1, 1), b)
+            ]
+        );
+    }
+}
@@ -1,7 +1,34 @@
 use super::HexCoord;
 use bevy_ecs::prelude::*;
+use thiserror::Error;
 
-#[derive(Event)]
+/// Triggered on a presence when it enters the zone at `position`.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct EnteredZone {
+    pub zone: Entity,
+    pub position: HexCoord,
+}
+
+/// Triggered on a presence when it leaves the zone at `position`.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct LeftZone {
+    pub zone: Entity,
+    pub position: HexCoord,
+}
+
+/// Triggered on a zone when a presence enters it.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct PresenceArrived {
+    pub presence: Entity,
+}
+
+/// Triggered on a zone when a presence leaves it, including when the presence is despawned.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct PresenceDeparted {
+    pub presence: Entity,
+}
+
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MapEvent {
     PresenceAdded {
         map: Entity,
@@ -19,3 +46,34 @@ pub enum MapEvent {
         position: HexCoord,
     },
 }
+
+impl MapEvent {
+    /// The map that the event happened on.
+    pub fn map(&self) -> Entity {
+        match self {
+            MapEvent::PresenceAdded { map, .. }
+            | MapEvent::PresenceMoved { map, .. }
+            | MapEvent::PresenceRemoved { map, .. } => *map,
+        }
+    }
+}
+
+#[derive(Error, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MapError {
+    #[error("position `{0}` is outside of the map")]
+    OutOfBounds(HexCoord),
+    #[error("map does not exist")]
+    MissingMap,
+    #[error("presence does not exist")]
+    MissingPresence,
+    #[error("presence is not on the map")]
+    NotOnMap,
+}
+
+/// Sent when a [`MapCommandsExt`](crate::MapCommandsExt) command could not be applied.
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MapCommandFailed {
+    pub map: Entity,
+    pub presence: Entity,
+    pub error: MapError,
+}
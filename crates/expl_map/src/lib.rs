@@ -3,9 +3,11 @@ mod component;
 mod event;
 mod plugin;
 mod system;
+mod system_param;
 
 pub use command::*;
 pub use component::*;
 pub use event::*;
 pub use expl_hexgrid::{layout::SquareGridLayout, HexCoord};
-pub use plugin::MapPlugin;
+pub use plugin::{MapAppExt, MapPlugin};
+pub use system_param::*;
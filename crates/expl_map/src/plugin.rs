@@ -8,23 +8,55 @@ pub struct MapPlugin;
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Fog>()
+            .register_type::<FactionFog>()
             .register_type::<FogRevealer>()
             .register_type::<HexCoord>()
             .register_type::<MapLayout>()
             .register_type::<MapPosition>()
             .register_type::<MapPresence>()
+            .register_type::<MapTurn>()
+            .register_type::<RevealerFaction>()
+            .register_type::<OnMap>()
             .register_type::<SquareGridLayout>()
             .register_type::<ViewRadius>()
+            .register_type::<ViewingFaction>()
+            .init_resource::<ViewingFaction>()
+            .init_resource::<MapTurn>()
             .add_systems(
                 Update,
                 (
-                    update_zone_visibility,
-                    log_moves,
-                    update_terrain_visibility.after(update_zone_visibility),
-                    update_presence_fog.after(update_zone_visibility),
-                )
-                    .run_if(on_event::<MapEvent>),
+                    update_zone_visibility.run_if(on_event::<MapEvent>.or(view_changed)),
+                    apply_viewing_faction.run_if(resource_changed::<ViewingFaction>),
+                    (update_terrain_visibility, update_presence_fog)
+                        .after(update_zone_visibility)
+                        .after(apply_viewing_faction)
+                        .run_if(
+                            on_event::<MapEvent>
+                                .or(view_changed)
+                                .or(resource_changed::<ViewingFaction>),
+                        ),
+                    log_failed_commands.run_if(on_event::<MapCommandFailed>),
+                ),
             )
-            .add_event::<MapEvent>();
+            .add_event::<MapEvent>()
+            .add_event::<MapCommandFailed>()
+            .add_observer(log_entered_zone);
+    }
+}
+
+pub trait MapAppExt {
+    /// Record the `T` component of the presences on zones that are visible to each faction in
+    /// [`LastSeen<T>`] on the zone.
+    fn remember_presences<T: Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl MapAppExt for App {
+    fn remember_presences<T: Component + Clone>(&mut self) -> &mut Self {
+        self.add_systems(
+            Update,
+            update_last_seen::<T>
+                .after(update_zone_visibility)
+                .run_if(on_event::<MapEvent>.or(view_changed)),
+        )
     }
 }
@@ -1,49 +1,164 @@
 use super::{component::*, event::*};
-use bevy_ecs::prelude::*;
-use bevy_log::info;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_log::{info, warn};
 use bevy_render::view::visibility::Visibility;
+use std::collections::HashSet;
 
-pub fn log_moves(
-    mut map_events: EventReader<MapEvent>,
-    presence_query: Query<&MapPresence>,
+pub fn log_entered_zone(
+    trigger: Trigger<EnteredZone>,
+    on_map_query: Query<&OnMap>,
     presence_layer_query: Query<&PresenceLayer>,
 ) {
-    let Ok(presence_layer) = presence_layer_query.single() else {
+    let entity = trigger.target();
+    info!("{:?} entered {}", entity, trigger.position);
+    let Some(presence_layer) = on_map_query
+        .get(entity)
+        .ok()
+        .and_then(|on_map| presence_layer_query.get(on_map.0).ok())
+    else {
         return;
     };
-    for event in map_events.read() {
-        if let MapEvent::PresenceMoved {
-            presence: entity,
-            position,
-            ..
-        } = event
-        {
-            info!("{:?} moved to {}", entity, position);
-            if let Ok(presence) = presence_query.get(*entity) {
-                for other in presence_layer
-                    .presence(presence.position)
-                    .filter(|e| *e != entity)
-                {
-                    info!("{:?} is here", other);
-                }
-            }
-        }
+    for other in presence_layer
+        .presence(trigger.position)
+        .filter(|&&e| e != entity)
+    {
+        info!("{:?} is here", other);
+    }
+}
+
+pub fn log_failed_commands(mut failed_events: EventReader<MapCommandFailed>) {
+    for failed in failed_events.read() {
+        warn!(
+            "map command for {:?} on {:?} failed: {}",
+            failed.presence, failed.map, failed.error
+        );
     }
 }
 
+/// Presences whose view of the map may have changed without them moving.
+type ViewChanged = Or<(
+    Changed<ViewRadius>,
+    Changed<FogRevealer>,
+    Changed<RevealerFaction>,
+)>;
+
+/// Whether what any presence can see has changed without it moving.
+pub fn view_changed(
+    view_query: Query<(), ViewChanged>,
+    mut removed_revealers: RemovedComponents<FogRevealer>,
+) -> bool {
+    let removed = removed_revealers.read().count() > 0;
+    !view_query.is_empty() || removed
+}
+
+/// Maps where presences have been added, moved or removed, or have changed what they can see.
+#[derive(SystemParam)]
+pub struct ChangedMaps<'w, 's> {
+    map_events: EventReader<'w, 's, MapEvent>,
+    view_query: Query<'w, 's, &'static OnMap, ViewChanged>,
+    removed_revealers: RemovedComponents<'w, 's, FogRevealer>,
+    on_map_query: Query<'w, 's, &'static OnMap>,
+}
+
+impl ChangedMaps<'_, '_> {
+    fn read(&mut self) -> HashSet<Entity> {
+        let removed: Vec<Entity> = self.removed_revealers.read().collect();
+        self.map_events
+            .read()
+            .map(MapEvent::map)
+            .chain(self.view_query.iter().map(|on_map| on_map.0))
+            .chain(self.on_map_query.iter_many(removed).map(|on_map| on_map.0))
+            .collect()
+    }
+}
+
+/// Update the fog of the zones on the maps where what presences can see has changed.
 pub fn update_zone_visibility(
-    view_query: Query<(&MapPresence, &ViewRadius), With<FogRevealer>>,
-    mut zone_query: Query<(&MapPosition, &mut Fog)>,
+    mut changed_maps: ChangedMaps,
+    viewing_faction: Res<ViewingFaction>,
+    view_query: Query<
+        (&OnMap, &MapPresence, &ViewRadius, Option<&RevealerFaction>),
+        With<FogRevealer>,
+    >,
+    mut zone_query: Query<(&OnMap, &MapPosition, &mut FactionFog, &mut Fog)>,
 ) {
-    for (position, mut fog) in zone_query.iter_mut() {
-        let visible = view_query
+    let maps = changed_maps.read();
+    for (zone_map, position, mut faction_fog, mut fog) in zone_query.iter_mut() {
+        if !maps.contains(&zone_map.0) {
+            continue;
+        }
+        let visible: FactionSet = view_query
             .iter()
-            .any(|(presence, view_radius)| position.0.distance(presence.position) < view_radius.0);
+            .filter(|(presence_map, presence, view_radius, _)| {
+                *presence_map == zone_map && position.0.distance(presence.position) < view_radius.0
+            })
+            .map(|(_, _, _, faction)| faction.map_or(Faction::PLAYER, |faction| faction.0))
+            .collect();
 
-        if visible != fog.visible {
-            fog.visible = visible;
-            if fog.visible {
-                fog.explored = true;
+        faction_fog.set_if_neq(FactionFog {
+            visible,
+            explored: faction_fog.explored.union(visible),
+        });
+        fog.set_if_neq(faction_fog.fog(**viewing_faction));
+    }
+}
+
+/// Show the fog of the new viewing faction.
+pub fn apply_viewing_faction(
+    viewing_faction: Res<ViewingFaction>,
+    mut zone_query: Query<(&FactionFog, &mut Fog)>,
+) {
+    for (faction_fog, mut fog) in &mut zone_query {
+        fog.set_if_neq(faction_fog.fog(**viewing_faction));
+    }
+}
+
+/// Record the presences on the zones that are visible to each faction.
+#[allow(clippy::type_complexity)]
+pub fn update_last_seen<T: Component + Clone>(
+    mut commands: Commands,
+    mut changed_maps: ChangedMaps,
+    turn: Res<MapTurn>,
+    map_query: Query<&PresenceLayer>,
+    presence_query: Query<&T, With<MapPresence>>,
+    mut zone_query: Query<(
+        Entity,
+        &OnMap,
+        &MapPosition,
+        &FactionFog,
+        Option<&mut LastSeen<T>>,
+    )>,
+) {
+    let maps = changed_maps.read();
+    for (zone, zone_map, position, faction_fog, last_seen) in &mut zone_query {
+        if faction_fog.visible.is_empty() || !maps.contains(&zone_map.0) {
+            continue;
+        }
+        let Ok(presence_layer) = map_query.get(zone_map.0) else {
+            continue;
+        };
+        let sightings: Vec<_> = presence_layer
+            .presence(position.0)
+            .filter_map(|&presence| {
+                presence_query.get(presence).ok().map(|data| Sighting {
+                    presence,
+                    data: data.clone(),
+                    turn: **turn,
+                })
+            })
+            .collect();
+
+        let update = |last_seen: &mut LastSeen<T>| {
+            for faction in faction_fog.visible.iter() {
+                last_seen.replace(faction, sightings.iter().cloned());
+            }
+        };
+        match last_seen {
+            Some(mut last_seen) => update(&mut last_seen),
+            None => {
+                let mut last_seen = LastSeen::default();
+                update(&mut last_seen);
+                commands.entity(zone).insert(last_seen);
             }
         }
     }
@@ -70,14 +185,14 @@ pub fn update_terrain_visibility(
 
 #[allow(clippy::type_complexity)]
 pub fn update_presence_fog(
-    zone_query: Query<(&MapPosition, &Fog), (Changed<Fog>, Without<MapPresence>)>,
+    zone_query: Query<(&OnMap, &MapPosition, &Fog), (Changed<Fog>, Without<MapPresence>)>,
     map_query: Query<&PresenceLayer>,
     mut presence_query: Query<(&mut Fog, &mut Visibility), With<MapPresence>>,
 ) {
-    let Ok(presence_layer) = map_query.single() else {
-        return;
-    };
-    for (position, zone_fog) in &zone_query {
+    for (zone_map, position, zone_fog) in &zone_query {
+        let Ok(presence_layer) = map_query.get(zone_map.0) else {
+            continue;
+        };
         let mut presence_iter = presence_query.iter_many_mut(presence_layer.presence(position.0));
         while let Some((mut fog, mut visibility)) = presence_iter.fetch_next() {
             fog.visible = zone_fog.visible;
@@ -89,3 +204,203 @@ pub fn update_presence_fog(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MapCommandsExt, SquareGridLayout};
+    use bevy_ecs::system::RunSystemOnce;
+    use expl_hexgrid::{GridLayout, HexCoord};
+
+    fn spawn_map(world: &mut World) -> (Entity, Vec<HexCoord>) {
+        world.init_resource::<Events<MapEvent>>();
+        world.init_resource::<ViewingFaction>();
+        let layout = SquareGridLayout {
+            width: 3,
+            height: 3,
+        };
+        let map = world.spawn(PresenceLayer::new(layout)).id();
+        let positions: Vec<_> = layout.iter().collect();
+        for &position in &positions {
+            world.spawn((
+                OnMap(map),
+                MapPosition(position),
+                FactionFog::default(),
+                Fog::default(),
+            ));
+        }
+        (map, positions)
+    }
+
+    fn spawn_revealer(
+        world: &mut World,
+        map: Entity,
+        position: HexCoord,
+        faction: Faction,
+    ) -> Entity {
+        world
+            .spawn((
+                OnMap(map),
+                MapPresence { position },
+                ViewRadius(1),
+                FogRevealer,
+                RevealerFaction(faction),
+            ))
+            .id()
+    }
+
+    fn fog_at(world: &mut World, position: HexCoord) -> (FactionFog, Fog) {
+        world
+            .query::<(&MapPosition, &FactionFog, &Fog)>()
+            .iter(world)
+            .find(|(zone_position, _, _)| zone_position.0 == position)
+            .map(|(_, faction_fog, fog)| (*faction_fog, *fog))
+            .unwrap()
+    }
+
+    #[derive(Component, Clone, PartialEq, Debug)]
+    struct Label(&'static str);
+
+    fn last_seen_at(
+        world: &mut World,
+        position: HexCoord,
+        faction: Faction,
+    ) -> Vec<(&'static str, u32)> {
+        world
+            .query::<(&MapPosition, &LastSeen<Label>)>()
+            .iter(world)
+            .find(|(zone_position, _)| zone_position.0 == position)
+            .map_or_else(Vec::new, |(_, last_seen)| {
+                last_seen
+                    .seen_by(faction)
+                    .map(|sighting| (sighting.data.0, sighting.turn))
+                    .collect()
+            })
+    }
+
+    fn factions(factions: &[Faction]) -> FactionSet {
+        factions.iter().copied().collect()
+    }
+
+    #[test]
+    fn view_radius_change_reveals() {
+        let mut world = World::new();
+        let (map, _) = spawn_map(&mut world);
+        let scout = spawn_revealer(&mut world, map, HexCoord::ZERO, Faction::PLAYER);
+        let visible = |world: &mut World| {
+            world
+                .query::<&Fog>()
+                .iter(world)
+                .filter(|fog| fog.visible)
+                .count()
+        };
+
+        let update = world.register_system(update_zone_visibility);
+        world.run_system(update).unwrap();
+        assert_eq!(visible(&mut world), 1);
+
+        world.get_mut::<ViewRadius>(scout).unwrap().0 = 2;
+        assert!(world.run_system_once(view_changed).unwrap());
+        world.run_system(update).unwrap();
+        assert!(visible(&mut world) > 1);
+    }
+
+    #[test]
+    fn faction_fog() {
+        let mut world = World::new();
+        let (map, positions) = spawn_map(&mut world);
+        let [home, far] = [positions[0], positions[positions.len() - 1]];
+        let presence = spawn_revealer(&mut world, map, home, Faction::PLAYER);
+        spawn_revealer(&mut world, map, far, Faction::ENEMY);
+
+        let update = world.register_system(update_zone_visibility);
+        world.run_system(update).unwrap();
+        let (home_fog, fog) = fog_at(&mut world, home);
+        assert_eq!(home_fog.visible, factions(&[Faction::PLAYER]));
+        assert_eq!(home_fog.explored, factions(&[Faction::PLAYER]));
+        assert!(fog.visible);
+        let (far_fog, fog) = fog_at(&mut world, far);
+        assert_eq!(far_fog.visible, factions(&[Faction::ENEMY]));
+        assert_eq!(far_fog.explored, factions(&[Faction::ENEMY]));
+        assert!(!fog.visible && !fog.explored);
+
+        world.entity_mut(presence).remove::<FogRevealer>();
+        assert!(world.run_system_once(view_changed).unwrap());
+        world.run_system(update).unwrap();
+        let (home_fog, fog) = fog_at(&mut world, home);
+        assert!(home_fog.visible.is_empty());
+        assert_eq!(home_fog.explored, factions(&[Faction::PLAYER]));
+        assert!(!fog.visible && fog.explored);
+
+        world
+            .entity_mut(presence)
+            .insert((FogRevealer, RevealerFaction(Faction::ENEMY)));
+        world.run_system(update).unwrap();
+        let (home_fog, _) = fog_at(&mut world, home);
+        assert_eq!(home_fog.visible, factions(&[Faction::ENEMY]));
+        assert_eq!(
+            home_fog.explored,
+            factions(&[Faction::PLAYER, Faction::ENEMY])
+        );
+
+        world.insert_resource(ViewingFaction(Faction::ENEMY));
+        world.run_system_once(apply_viewing_faction).unwrap();
+        assert!(fog_at(&mut world, home).1.visible);
+    }
+
+    #[test]
+    fn last_seen() {
+        let mut world = World::new();
+        world.init_resource::<MapTurn>();
+        let (map, _) = spawn_map(&mut world);
+        let [home, away, slime_zone] = [HexCoord::ZERO, HexCoord::new(1, 2), HexCoord::new(1, 0)];
+        let scout = world
+            .spawn((ViewRadius(2), FogRevealer, Label("scout")))
+            .id();
+        let slime = world.spawn(Label("slime")).id();
+        world
+            .commands()
+            .entity(map)
+            .add_presence(scout, home)
+            .add_presence(slime, slime_zone);
+        world.flush();
+
+        let update_visibility = world.register_system(update_zone_visibility);
+        let update = world.register_system(update_last_seen::<Label>);
+        world.run_system(update_visibility).unwrap();
+        world.run_system(update).unwrap();
+        assert_eq!(
+            last_seen_at(&mut world, home, Faction::PLAYER),
+            [("scout", 0)]
+        );
+        assert_eq!(
+            last_seen_at(&mut world, slime_zone, Faction::PLAYER),
+            [("slime", 0)]
+        );
+        assert_eq!(last_seen_at(&mut world, away, Faction::PLAYER), []);
+        assert_eq!(last_seen_at(&mut world, slime_zone, Faction::ENEMY), []);
+
+        world.insert_resource(MapTurn(1));
+        world
+            .commands()
+            .entity(map)
+            .move_presence(scout, away)
+            .move_presence(slime, home);
+        world.flush();
+        world.run_system(update_visibility).unwrap();
+        world.run_system(update).unwrap();
+        assert_eq!(
+            last_seen_at(&mut world, away, Faction::PLAYER),
+            [("scout", 1)]
+        );
+        // The zones the scout left keep showing what was there when it saw them
+        assert_eq!(
+            last_seen_at(&mut world, home, Faction::PLAYER),
+            [("scout", 0)]
+        );
+        assert_eq!(
+            last_seen_at(&mut world, slime_zone, Faction::PLAYER),
+            [("slime", 0)]
+        );
+    }
+}
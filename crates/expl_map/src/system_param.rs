@@ -0,0 +1,141 @@
+use super::component::*;
+use bevy_ecs::{
+    prelude::*,
+    query::{QueryFilter, ROQueryItem, ReadOnlyQueryData},
+    system::SystemParam,
+};
+use expl_hexgrid::HexCoord;
+
+/// Looks up what each faction can see of the maps.
+#[derive(SystemParam)]
+pub struct FactionVisibility<'w, 's> {
+    map_query: Query<'w, 's, &'static ZoneLayer>,
+    fog_query: Query<'w, 's, &'static FactionFog>,
+}
+
+impl FactionVisibility<'_, '_> {
+    pub fn fog(&self, map: Entity, position: HexCoord) -> Option<&FactionFog> {
+        let zone = self.map_query.get(map).ok()?.get(position)?;
+        self.fog_query.get(*zone).ok()
+    }
+
+    pub fn is_visible(&self, map: Entity, position: HexCoord, faction: Faction) -> bool {
+        self.fog(map, position)
+            .is_some_and(|fog| fog.is_visible_to(faction))
+    }
+
+    pub fn is_explored(&self, map: Entity, position: HexCoord, faction: Faction) -> bool {
+        self.fog(map, position)
+            .is_some_and(|fog| fog.is_explored_by(faction))
+    }
+}
+
+/// Presences on a map that match the query data `D` and filter `F`, looked up by position.
+#[derive(SystemParam)]
+pub struct Presences<'w, 's, D, F = ()>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    map_query: Query<'w, 's, &'static PresenceLayer>,
+    query: Query<'w, 's, D, F>,
+}
+
+impl<D, F> Presences<'_, '_, D, F>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    /// Matching presences at `position`.
+    pub fn at(&self, map: Entity, position: HexCoord) -> impl Iterator<Item = ROQueryItem<'_, D>> {
+        self.map_query
+            .get(map)
+            .into_iter()
+            .flat_map(move |layer| self.query.iter_many(layer.presence(position)))
+    }
+
+    /// Number of matching presences at `position`.
+    pub fn count(&self, map: Entity, position: HexCoord) -> usize {
+        self.at(map, position).count()
+    }
+
+    /// Matching presences within `radius` of `center`, ordered by their distance to `center`.
+    pub fn within(
+        &self,
+        map: Entity,
+        center: HexCoord,
+        radius: u32,
+    ) -> impl Iterator<Item = (HexCoord, ROQueryItem<'_, D>)> {
+        self.map_query
+            .get(map)
+            .into_iter()
+            .flat_map(move |layer| layer.presence_within(center, radius))
+            .filter_map(|(position, entity)| {
+                self.query.get(entity).ok().map(|item| (position, item))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use expl_hexgrid::layout::SquareGridLayout;
+
+    #[derive(Component)]
+    struct Marker;
+
+    fn setup() -> (World, Entity, [Entity; 3]) {
+        let mut world = World::new();
+        let map = world
+            .spawn(PresenceLayer::new(SquareGridLayout {
+                width: 3,
+                height: 3,
+            }))
+            .id();
+        let a = world.spawn(Marker).id();
+        let b = world.spawn_empty().id();
+        let c = world.spawn(Marker).id();
+        let mut layer = world.get_mut::<PresenceLayer>(map).unwrap();
+        layer.add_presence(HexCoord::ZERO, a);
+        layer.add_presence(HexCoord::ZERO, b);
+        layer.add_presence(HexCoord::new(1, 1), c);
+        (world, map, [a, b, c])
+    }
+
+    #[test]
+    fn presences_at() {
+        let (mut world, map, [a, _, _]) = setup();
+        let (at, count, unknown) = world
+            .run_system_once(move |presences: Presences<Entity, With<Marker>>| {
+                (
+                    presences.at(map, HexCoord::ZERO).collect::<Vec<_>>(),
+                    presences.count(map, HexCoord::ZERO),
+                    presences.count(Entity::PLACEHOLDER, HexCoord::ZERO),
+                )
+            })
+            .unwrap();
+        assert_eq!(at, [a]);
+        assert_eq!(count, 1);
+        assert_eq!(unknown, 0);
+    }
+
+    #[test]
+    fn presences_within() {
+        let (mut world, map, [a, _, c]) = setup();
+        let (near, all) = world
+            .run_system_once(move |presences: Presences<Entity, With<Marker>>| {
+                (
+                    presences
+                        .within(map, HexCoord::new(1, 1), 0)
+                        .collect::<Vec<_>>(),
+                    presences
+                        .within(map, HexCoord::new(1, 1), u32::MAX)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .unwrap();
+        assert_eq!(near, [(HexCoord::new(1, 1), c)]);
+        assert_eq!(all, [(HexCoord::new(1, 1), c), (HexCoord::ZERO, a)]);
+    }
+}
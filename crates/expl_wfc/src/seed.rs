@@ -100,6 +100,12 @@ impl From<Seed> for rand_xoshiro::Xoshiro256PlusPlus {
     }
 }
 
+impl From<Seed> for rand::rngs::StdRng {
+    fn from(seed: Seed) -> Self {
+        Self::seed_from_u64(seed.rng_seed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Seed, SeedType, WFCError};
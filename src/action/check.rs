@@ -0,0 +1,523 @@
+use super::{
+    component::Scouting,
+    queue::{ActionCheckResult, GameAction},
+};
+use crate::{
+    actor::{Enemy, Members, Party},
+    combat::Combat,
+    inventory::Inventory,
+    structure::{Camp, Portal, SafeHaven},
+    terrain::{CrystalDeposit, Depletion, SupplySource, TerrainCodex, TerrainId},
+    turn::Period,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use expl_map::{MapPosition, MapPresence, OnMap, PresenceLayer, Presences, ViewRadius, ZoneLayer};
+use smallvec::SmallVec;
+use thiserror::Error;
+
+/// Why an action can not be performed right now, shown to the player.
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ActionBlocked {
+    #[error("Nothing selected")]
+    NothingSelected,
+    #[error("Selection can not do this")]
+    InvalidSource,
+    #[error("Invalid target")]
+    InvalidTarget,
+    #[error("No action points left")]
+    NoActionPoints,
+    #[error("Can not build on this terrain")]
+    BadTerrain,
+    #[error("There is already a camp here")]
+    ExistingCamp,
+    #[error("There is no camp here")]
+    NoCamp,
+    #[error("The camp is not empty")]
+    CampNotEmpty,
+    #[error("Not enough supplies")]
+    MissingSupplies,
+    #[error("Missing required items")]
+    MissingItems,
+    #[error("Can not split off the whole party")]
+    InvalidSplit,
+    #[error("The parties are not in the same zone")]
+    NotInSameZone,
+    #[error("There are no crystals here")]
+    NoCrystals,
+    #[error("There is no portal here")]
+    NoPortal,
+    #[error("The portal is already open")]
+    PortalOpen,
+    #[error("The portal is not open")]
+    PortalClosed,
+    #[error("Nothing to be found here")]
+    NothingToGather,
+    #[error("Nothing to transfer")]
+    NothingToTransfer,
+    #[error("There are no enemies there")]
+    NoEnemy,
+    #[error("There are enemies there")]
+    EnemyPresent,
+    #[error("Already in combat")]
+    InCombat,
+    #[error("Not in combat")]
+    NotInCombat,
+    #[error("Already scouting")]
+    AlreadyScouting,
+}
+
+fn zone_at(
+    zone_layer_query: &Query<&ZoneLayer>,
+    presence: &MapPresence,
+    on_map: &OnMap,
+) -> Result<Entity, ActionBlocked> {
+    zone_layer_query
+        .get(on_map.0)
+        .ok()
+        .and_then(|zone_layer| zone_layer.get(presence.position))
+        .copied()
+        .ok_or(ActionBlocked::InvalidSource)
+}
+
+fn presence_at<'a>(
+    presence_layer_query: &'a Query<&PresenceLayer>,
+    presence: &MapPresence,
+    on_map: &OnMap,
+) -> Result<impl Iterator<Item = &'a Entity>, ActionBlocked> {
+    presence_layer_query
+        .get(on_map.0)
+        .map(|presence_layer| presence_layer.presence(presence.position))
+        .map_err(|_| ActionBlocked::InvalidSource)
+}
+
+pub fn can_move(
+    In(action): In<GameAction>,
+    party_query: Query<(), (With<MapPresence>, With<OnMap>)>,
+    zone_query: Query<(), With<MapPosition>>,
+) -> ActionCheckResult {
+    party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    action
+        .targets
+        .first()
+        .filter(|&&target| zone_query.contains(target))
+        .ok_or(ActionBlocked::InvalidTarget)?;
+    Ok(())
+}
+
+pub fn can_make_camp(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    zone_layer_query: Query<&ZoneLayer>,
+    presence_layer_query: Query<&PresenceLayer>,
+    terrain_query: Query<&TerrainId>,
+    camp_query: Query<(), With<Camp>>,
+    terrain_codex: TerrainCodex,
+) -> ActionCheckResult {
+    let (presence, on_map) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    let zone = zone_at(&zone_layer_query, presence, on_map)?;
+    if let Some((terrain_codex, terrain_id)) =
+        terrain_codex.get().ok().zip(terrain_query.get(zone).ok())
+    {
+        if !terrain_codex[terrain_id].allow_structure {
+            return Err(ActionBlocked::BadTerrain);
+        }
+    }
+    if camp_query
+        .iter_many(presence_at(&presence_layer_query, presence, on_map)?)
+        .next()
+        .is_some()
+    {
+        return Err(ActionBlocked::ExistingCamp);
+    }
+    Ok(())
+}
+
+pub fn can_break_camp(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    presence_layer_query: Query<&PresenceLayer>,
+    camp_query: Query<&Members, With<Camp>>,
+) -> ActionCheckResult {
+    let (presence, on_map) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    let members = camp_query
+        .iter_many(presence_at(&presence_layer_query, presence, on_map)?)
+        .next()
+        .ok_or(ActionBlocked::NoCamp)?;
+    if !members.is_empty() {
+        return Err(ActionBlocked::CampNotEmpty);
+    }
+    Ok(())
+}
+
+pub fn can_enter_camp(
+    In(action): In<GameAction>,
+    party_query: Query<(), With<Party>>,
+    camp_query: Query<(), With<Camp>>,
+) -> ActionCheckResult {
+    party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    action
+        .targets
+        .first()
+        .filter(|&&camp| camp_query.contains(camp))
+        .ok_or(ActionBlocked::NoCamp)?;
+    Ok(())
+}
+
+/// The selected characters must all be members of the group.
+fn check_split(members: &Members, characters: &[Entity]) -> ActionCheckResult {
+    if characters.is_empty() {
+        return Err(ActionBlocked::NothingSelected);
+    }
+    if !characters
+        .iter()
+        .all(|character| members.contains(character))
+    {
+        return Err(ActionBlocked::InvalidTarget);
+    }
+    Ok(())
+}
+
+pub fn can_split_party(
+    In(action): In<GameAction>,
+    party_query: Query<&Members, With<Party>>,
+) -> ActionCheckResult {
+    let members = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    check_split(members, &action.targets)?;
+    if members.len() == action.targets.len() {
+        return Err(ActionBlocked::InvalidSplit);
+    }
+    Ok(())
+}
+
+pub fn can_create_party_from_camp(
+    In(action): In<GameAction>,
+    camp_query: Query<&Members, With<Camp>>,
+) -> ActionCheckResult {
+    let members = camp_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    check_split(members, &action.targets)
+}
+
+pub fn can_merge_party(
+    In(action): In<GameAction>,
+    party_query: Query<&MapPresence, With<Party>>,
+) -> ActionCheckResult {
+    let source = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    if action.targets.is_empty() {
+        return Err(ActionBlocked::NothingSelected);
+    }
+    for &target in &action.targets {
+        let presence = party_query
+            .get(target)
+            .map_err(|_| ActionBlocked::InvalidTarget)?;
+        if presence.position != source.position {
+            return Err(ActionBlocked::NotInSameZone);
+        }
+    }
+    Ok(())
+}
+
+pub fn can_collect_crystals(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    zone_layer_query: Query<&ZoneLayer>,
+    crystal_deposit_query: Query<&CrystalDeposit>,
+) -> ActionCheckResult {
+    let (presence, on_map) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    let zone = zone_at(&zone_layer_query, presence, on_map)?;
+    match crystal_deposit_query.get(zone) {
+        Ok(crystal_deposit) if crystal_deposit.amount > 0 => Ok(()),
+        _ => Err(ActionBlocked::NoCrystals),
+    }
+}
+
+fn portal_here<'a>(
+    action: &GameAction,
+    party_query: &Query<(&MapPresence, &OnMap), With<Party>>,
+    presence_layer_query: &Query<&PresenceLayer>,
+    portal_query: &'a Query<&Portal>,
+) -> Result<&'a Portal, ActionBlocked> {
+    let (presence, on_map) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    portal_query
+        .iter_many(presence_at(presence_layer_query, presence, on_map)?)
+        .next()
+        .ok_or(ActionBlocked::NoPortal)
+}
+
+pub fn can_open_portal(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    presence_layer_query: Query<&PresenceLayer>,
+    portal_query: Query<&Portal>,
+) -> ActionCheckResult {
+    let portal = portal_here(&action, &party_query, &presence_layer_query, &portal_query)?;
+    if portal.open {
+        return Err(ActionBlocked::PortalOpen);
+    }
+    Ok(())
+}
+
+pub fn can_enter_portal(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    presence_layer_query: Query<&PresenceLayer>,
+    portal_query: Query<&Portal>,
+) -> ActionCheckResult {
+    let portal = portal_here(&action, &party_query, &presence_layer_query, &portal_query)?;
+    if !portal.open {
+        return Err(ActionBlocked::PortalClosed);
+    }
+    Ok(())
+}
+
+/// Supplies that a party would find by foraging or hunting where it is.
+#[derive(SystemParam)]
+pub struct SupplyYield<'w, 's> {
+    party_query: Query<'w, 's, (&'static MapPresence, &'static OnMap), With<Party>>,
+    zone_layer_query: Query<'w, 's, &'static ZoneLayer>,
+    zone_query: Query<'w, 's, (&'static TerrainId, Option<&'static Depletion>)>,
+    terrain_codex: TerrainCodex<'w>,
+    period: Option<Res<'w, Period>>,
+}
+
+impl SupplyYield<'_, '_> {
+    /// The zone, the amount found and the depletion of the zone before gathering.
+    pub fn get(
+        &self,
+        party: Entity,
+        source: SupplySource,
+    ) -> Result<(Entity, u32, Depletion), ActionBlocked> {
+        let (presence, on_map) = self
+            .party_query
+            .get(party)
+            .map_err(|_| ActionBlocked::InvalidSource)?;
+        let zone = zone_at(&self.zone_layer_query, presence, on_map)?;
+        let (terrain_id, depletion) = self
+            .zone_query
+            .get(zone)
+            .map_err(|_| ActionBlocked::InvalidSource)?;
+        let depletion = depletion.copied().unwrap_or_default();
+        let period = self.period.as_deref().copied().unwrap_or_default();
+        let amount = self
+            .terrain_codex
+            .get()
+            .ok()
+            .and_then(|terrain_codex| terrain_codex.get(terrain_id))
+            .map_or(0, |terrain| terrain.yield_table(source).get(period));
+        match depletion.diminish(source, amount) {
+            0 => Err(ActionBlocked::NothingToGather),
+            amount => Ok((zone, amount, depletion)),
+        }
+    }
+}
+
+pub fn can_forage(In(action): In<GameAction>, supply_yield: SupplyYield) -> ActionCheckResult {
+    supply_yield
+        .get(action.source, SupplySource::Forage)
+        .map(|_| ())
+}
+
+pub fn can_hunt(In(action): In<GameAction>, supply_yield: SupplyYield) -> ActionCheckResult {
+    supply_yield
+        .get(action.source, SupplySource::Hunt)
+        .map(|_| ())
+}
+
+/// Inventories that are close enough to hand items to each other, parties and camps in the same
+/// zone or the safe haven for a party at an open portal.
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub struct TransferPartners<'w, 's> {
+    holder_query: Query<
+        'w,
+        's,
+        (
+            Option<&'static MapPresence>,
+            Option<&'static OnMap>,
+            Has<SafeHaven>,
+        ),
+        (
+            With<Inventory>,
+            Or<(With<Party>, With<Camp>, With<SafeHaven>)>,
+        ),
+    >,
+    presence_layer_query: Query<'w, 's, &'static PresenceLayer>,
+    portal_query: Query<'w, 's, &'static Portal>,
+    safe_haven_query: Query<'w, 's, Entity, (With<SafeHaven>, With<Inventory>)>,
+}
+
+impl TransferPartners<'_, '_> {
+    fn open_portal_at(&self, presence: &MapPresence, on_map: &OnMap) -> ActionCheckResult {
+        let portal = self
+            .portal_query
+            .iter_many(presence_at(&self.presence_layer_query, presence, on_map)?)
+            .next()
+            .ok_or(ActionBlocked::NoPortal)?;
+        if !portal.open {
+            return Err(ActionBlocked::PortalClosed);
+        }
+        Ok(())
+    }
+
+    pub fn check(&self, source: Entity, target: Entity) -> ActionCheckResult {
+        let source_holder = self
+            .holder_query
+            .get(source)
+            .map_err(|_| ActionBlocked::InvalidSource)?;
+        let target_holder = self
+            .holder_query
+            .get(target)
+            .map_err(|_| ActionBlocked::InvalidTarget)?;
+        if source == target {
+            return Err(ActionBlocked::InvalidTarget);
+        }
+        match (source_holder, target_holder) {
+            ((Some(source_presence), Some(source_map), _), (Some(presence), Some(on_map), _)) => {
+                if source_map != on_map || source_presence.position != presence.position {
+                    return Err(ActionBlocked::NotInSameZone);
+                }
+                Ok(())
+            }
+            ((Some(presence), Some(on_map), _), (_, _, true))
+            | ((_, _, true), (Some(presence), Some(on_map), _)) => {
+                self.open_portal_at(presence, on_map)
+            }
+            _ => Err(ActionBlocked::InvalidTarget),
+        }
+    }
+
+    /// Every inventory that `entity` can transfer items to and from.
+    pub fn get(&self, entity: Entity) -> SmallVec<[Entity; 4]> {
+        let Ok((Some(presence), Some(on_map), _)) = self.holder_query.get(entity) else {
+            return SmallVec::new();
+        };
+        let Ok(presence_here) = presence_at(&self.presence_layer_query, presence, on_map) else {
+            return SmallVec::new();
+        };
+        presence_here
+            .copied()
+            .chain(self.safe_haven_query.iter())
+            .filter(|&partner| self.check(entity, partner).is_ok())
+            .collect()
+    }
+}
+
+pub fn can_transfer_items(
+    In(action): In<GameAction>,
+    transfer_partners: TransferPartners,
+    inventory_query: Query<&Inventory>,
+) -> ActionCheckResult {
+    let target = action
+        .targets
+        .first()
+        .copied()
+        .ok_or(ActionBlocked::InvalidTarget)?;
+    transfer_partners.check(action.source, target)?;
+    if action.items.iter().all(|&(_, count)| count == 0) {
+        return Err(ActionBlocked::NothingToTransfer);
+    }
+    let inventory = inventory_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    if action
+        .items
+        .iter()
+        .any(|&(item_id, count)| inventory.count_item(item_id) < count)
+    {
+        return Err(ActionBlocked::MissingItems);
+    }
+    Ok(())
+}
+
+/// The zone that is the target of `action` if it is next to `presence`.
+fn adjacent_target<'a>(
+    action: &GameAction,
+    presence: &MapPresence,
+    zone_query: &'a Query<(&MapPosition, Option<&TerrainId>)>,
+) -> Result<(&'a MapPosition, Option<&'a TerrainId>), ActionBlocked> {
+    action
+        .targets
+        .first()
+        .and_then(|&target| zone_query.get(target).ok())
+        .filter(|(position, _)| presence.position.distance(position.0) == 1)
+        .ok_or(ActionBlocked::InvalidTarget)
+}
+
+pub fn can_attack(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
+    zone_query: Query<(&MapPosition, Option<&TerrainId>)>,
+    foe_presences: Presences<(), With<Enemy>>,
+    combat_query: Query<(), With<Combat>>,
+) -> ActionCheckResult {
+    let (presence, on_map) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    let (target, _) = adjacent_target(&action, presence, &zone_query)?;
+    if foe_presences.count(on_map.0, target.0) == 0 {
+        return Err(ActionBlocked::NoEnemy);
+    }
+    if !combat_query.is_empty() {
+        return Err(ActionBlocked::InCombat);
+    }
+    Ok(())
+}
+
+pub fn can_retreat(
+    In(action): In<GameAction>,
+    party_query: Query<(&MapPresence, &OnMap, &Members), With<Party>>,
+    zone_query: Query<(&MapPosition, Option<&TerrainId>)>,
+    foe_presences: Presences<(), With<Enemy>>,
+    combat_query: Query<&Combat>,
+    terrain_codex: TerrainCodex,
+) -> ActionCheckResult {
+    let (presence, on_map, members) = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    if !combat_query
+        .iter()
+        .any(|combat| members.iter().any(|&member| combat.contains(member)))
+    {
+        return Err(ActionBlocked::NotInCombat);
+    }
+    let (target, terrain_id) = adjacent_target(&action, presence, &zone_query)?;
+    if let Some((terrain_codex, terrain_id)) = terrain_codex.get().ok().zip(terrain_id) {
+        if !terrain_codex[terrain_id].allow_walking {
+            return Err(ActionBlocked::BadTerrain);
+        }
+    }
+    if foe_presences.count(on_map.0, target.0) > 0 {
+        return Err(ActionBlocked::EnemyPresent);
+    }
+    Ok(())
+}
+
+pub fn can_scout(
+    In(action): In<GameAction>,
+    party_query: Query<Has<Scouting>, (With<Party>, With<ViewRadius>, With<MapPresence>)>,
+) -> ActionCheckResult {
+    let scouting = party_query
+        .get(action.source)
+        .map_err(|_| ActionBlocked::InvalidSource)?;
+    if scouting {
+        return Err(ActionBlocked::AlreadyScouting);
+    }
+    Ok(())
+}
@@ -1,4 +1,3 @@
-use crate::ExplError;
 use bevy::prelude::*;
 
 #[derive(Component, Reflect, Default, Debug)]
@@ -19,13 +18,11 @@ impl ActionPoints {
     pub fn reset(&mut self) {
         self.current = self.reset;
     }
+}
 
-    pub fn consume(&mut self) -> Result<(), ExplError> {
-        if self.current == 0 {
-            Err(ExplError::MoveWithoutMovementPoints)
-        } else {
-            self.current -= 1;
-            Ok(())
-        }
-    }
+/// View radius that a scouting party has gained for the rest of the turn.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Scouting {
+    pub bonus: u32,
 }
@@ -0,0 +1,154 @@
+use super::{check::ActionBlocked, component::ActionPoints, queue::*};
+use crate::{
+    actor::Members,
+    assets::CodexAssets,
+    inventory::{Inventory, Item},
+    terrain::{Terrain, TerrainId},
+};
+use bevy::prelude::*;
+use expl_codex::{Codex, Id};
+use expl_map::{MapPresence, OnMap, ZoneLayer};
+use smallvec::SmallVec;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ActionPointCost {
+    #[default]
+    Free,
+    Fixed(u16),
+    /// The move cost of the terrain of the target zone, or of the zone the source is in.
+    Terrain,
+}
+
+/// What an action takes from its source when it is applied.
+#[derive(Clone, Debug, Default)]
+pub struct ActionCost {
+    pub action_points: ActionPointCost,
+    pub items: SmallVec<[(Id<Item>, u32); 2]>,
+}
+
+impl ActionCost {
+    pub fn free() -> Self {
+        Self::default()
+    }
+
+    pub fn action_points(action_points: u16) -> Self {
+        Self {
+            action_points: ActionPointCost::Fixed(action_points),
+            ..default()
+        }
+    }
+
+    pub fn terrain() -> Self {
+        Self {
+            action_points: ActionPointCost::Terrain,
+            ..default()
+        }
+    }
+
+    pub fn with_item(mut self, item_id: Id<Item>, count: u32) -> Self {
+        self.items.push((item_id, count));
+        self
+    }
+
+    /// Work out the cost of `action` in the current state of the world.
+    pub(super) fn resolve(&self, world: &World, action: &GameAction) -> ResolvedCost {
+        let action_points = match self.action_points {
+            ActionPointCost::Free => 0,
+            ActionPointCost::Fixed(action_points) => action_points,
+            ActionPointCost::Terrain => terrain_move_cost(world, action).unwrap_or(1),
+        };
+        ResolvedCost {
+            action_points,
+            items: self.items.clone(),
+        }
+    }
+}
+
+fn terrain_move_cost(world: &World, action: &GameAction) -> Option<u16> {
+    let zone = match action.targets.first() {
+        Some(&target) if world.get::<TerrainId>(target).is_some() => target,
+        _ => {
+            let &OnMap(map) = world.get::<OnMap>(action.source)?;
+            let position = world.get::<MapPresence>(action.source)?.position;
+            *world.get::<ZoneLayer>(map)?.get(position)?
+        }
+    };
+    let terrain_id = world.get::<TerrainId>(zone)?;
+    let codex_assets = world.get_resource::<CodexAssets>()?;
+    world
+        .get_resource::<Assets<Codex<Terrain>>>()?
+        .get(&codex_assets.terrain_codex)?
+        .get(terrain_id)
+        .map(Terrain::move_cost)
+}
+
+/// The cost of a specific action, taken from the source before the action is applied and given
+/// back if the action fails.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct ResolvedCost {
+    pub action_points: u16,
+    pub items: SmallVec<[(Id<Item>, u32); 2]>,
+}
+
+impl ResolvedCost {
+    pub fn check(&self, world: &World, source: Entity) -> ActionCheckResult {
+        if self.action_points > 0
+            && world
+                .get::<ActionPoints>(source)
+                .is_some_and(|action_points| action_points.current < self.action_points)
+        {
+            return Err(ActionBlocked::NoActionPoints);
+        }
+        let inventory = world.get::<Inventory>(source);
+        for &(item_id, count) in &self.items {
+            if inventory.is_none_or(|inventory| inventory.count_item(item_id) < count) {
+                return Err(if item_id == Inventory::SUPPLY {
+                    ActionBlocked::MissingSupplies
+                } else {
+                    ActionBlocked::MissingItems
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn pay(&self, world: &mut World, source: Entity) -> ActionCheckResult {
+        self.check(world, source)?;
+        self.apply(world, source, |action_points, cost| {
+            action_points.current -= cost.min(action_points.current);
+        });
+        if let Some(mut inventory) = world.get_mut::<Inventory>(source) {
+            for &(item_id, count) in &self.items {
+                inventory.take_item(item_id, count).ok();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn refund(&self, world: &mut World, source: Entity) {
+        self.apply(world, source, |action_points, cost| {
+            action_points.current = (action_points.current + cost).min(action_points.reset);
+        });
+        if let Some(mut inventory) = world.get_mut::<Inventory>(source) {
+            for &(item_id, count) in &self.items {
+                inventory.add_item(item_id, count);
+            }
+        }
+    }
+
+    /// Change the action points of the source and the members of the group.
+    fn apply(&self, world: &mut World, source: Entity, f: impl Fn(&mut ActionPoints, u16)) {
+        if self.action_points == 0 {
+            return;
+        }
+        let members: SmallVec<[Entity; 8]> = world
+            .get::<Members>(source)
+            .map(|members| members.iter().copied().collect())
+            .unwrap_or_default();
+        for entity in std::iter::once(source).chain(members) {
+            if let Some(mut action_points) = world.get_mut::<ActionPoints>(entity) {
+                f(&mut action_points, self.action_points);
+            }
+        }
+    }
+}
@@ -1,19 +1,34 @@
+mod check;
 mod component;
-mod event;
+mod cost;
 mod plugin;
 mod queue;
 mod system;
+mod undo;
 
-pub use component::ActionPoints;
+pub use check::{ActionBlocked, TransferPartners};
+pub use component::{ActionPoints, Scouting};
+pub use cost::{ActionCost, ActionPointCost};
 pub use plugin::{ActionPlugin, ActionUpdate};
-pub use queue::{GameAction, GameActionQueue, GameActionType};
+pub use queue::{ActionCheckResult, GameAction, GameActionQueue, GameActionType, GameActions};
+pub use system::apply_action;
+pub use undo::{can_undo, undo_last_action, UndoStack};
 
 #[cfg(test)]
 mod tests {
-    use super::{ActionPlugin, ActionPoints};
-    use crate::actor::{GroupCommandsExt, Members};
-    use bevy::prelude::*;
+    use super::{
+        cost::ResolvedCost, system::end_scouting, ActionBlocked, ActionPlugin, ActionPoints,
+        GameAction, GameActions, Scouting,
+    };
+    use crate::{
+        actor::{Enemy, GroupCommandsExt, Members, Party},
+        inventory::Inventory,
+        test_fixture::spawn_test_map,
+    };
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+    use expl_map::{HexCoord, MapPresence, OnMap, PresenceLayer, ViewRadius, ZoneLayer};
     use rstest::*;
+    use smallvec::smallvec;
 
     #[fixture]
     fn app() -> App {
@@ -85,4 +100,190 @@ mod tests {
             .add_members(&[member]);
         app.world_mut().flush();
     }
+
+    #[rstest]
+    fn can_perform_split_party(mut app: App) {
+        let party = app
+            .world_mut()
+            .spawn((Party::default(), Members::default()))
+            .id();
+        let members = [
+            app.world_mut().spawn_empty().id(),
+            app.world_mut().spawn_empty().id(),
+        ];
+        app.world_mut()
+            .commands()
+            .entity(party)
+            .add_members(&members);
+        app.world_mut().flush();
+
+        let world = app.world_mut();
+        assert_eq!(
+            GameActions::can_perform(world, &GameAction::new_split_party(party, [members[0]])),
+            Ok(())
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &GameAction::new_split_party(party, members)),
+            Err(ActionBlocked::InvalidSplit)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &GameAction::new_split_party(party, [])),
+            Err(ActionBlocked::NothingSelected)
+        );
+    }
+
+    #[rstest]
+    fn can_perform_transfer_items(mut app: App) {
+        let world = app.world_mut();
+        let map = world.spawn_empty().id();
+        let mut inventory = Inventory::default();
+        inventory.add_item(Inventory::SUPPLY, 2);
+        let [giver, taker, far_away] = [
+            (HexCoord::ZERO, inventory),
+            (HexCoord::ZERO, Inventory::default()),
+            (HexCoord::new(1, 0), Inventory::default()),
+        ]
+        .map(|(position, inventory)| {
+            world
+                .spawn((
+                    Party::default(),
+                    MapPresence { position },
+                    OnMap(map),
+                    inventory,
+                ))
+                .id()
+        });
+
+        let transfer = |target, count| {
+            GameAction::new_transfer_items(giver, target, [(Inventory::SUPPLY, count)])
+        };
+        assert_eq!(GameActions::can_perform(world, &transfer(taker, 2)), Ok(()));
+        assert_eq!(
+            GameActions::can_perform(world, &transfer(taker, 3)),
+            Err(ActionBlocked::MissingItems)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &transfer(taker, 0)),
+            Err(ActionBlocked::NothingToTransfer)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &transfer(far_away, 1)),
+            Err(ActionBlocked::NotInSameZone)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &transfer(giver, 1)),
+            Err(ActionBlocked::InvalidTarget)
+        );
+    }
+
+    #[rstest]
+    fn can_perform_attack(mut app: App) {
+        let world = app.world_mut();
+        let map = spawn_test_map(world);
+        let zone_layer = world.get::<ZoneLayer>(map).unwrap();
+        let zone_at = |position| *zone_layer.get(position).unwrap();
+        let [here, enemy_zone, empty_zone, far_zone] = [
+            HexCoord::ZERO,
+            HexCoord::new(1, 0),
+            HexCoord::new(0, 1),
+            HexCoord::new(2, 0),
+        ]
+        .map(zone_at);
+        let [party, enemy] = [
+            world
+                .spawn((
+                    Party::default(),
+                    Members::default(),
+                    MapPresence {
+                        position: HexCoord::ZERO,
+                    },
+                    OnMap(map),
+                ))
+                .id(),
+            world
+                .spawn((
+                    Enemy,
+                    MapPresence {
+                        position: HexCoord::new(1, 0),
+                    },
+                    OnMap(map),
+                ))
+                .id(),
+        ];
+        let mut presence_layer = world.get_mut::<PresenceLayer>(map).unwrap();
+        presence_layer.add_presence(HexCoord::ZERO, party);
+        presence_layer.add_presence(HexCoord::new(1, 0), enemy);
+
+        let attack = |zone| GameAction::new_attack(party, zone);
+        assert_eq!(GameActions::can_perform(world, &attack(enemy_zone)), Ok(()));
+        assert_eq!(
+            GameActions::can_perform(world, &attack(empty_zone)),
+            Err(ActionBlocked::NoEnemy)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &attack(far_zone)),
+            Err(ActionBlocked::InvalidTarget)
+        );
+        assert_eq!(
+            GameActions::can_perform(world, &attack(here)),
+            Err(ActionBlocked::InvalidTarget)
+        );
+    }
+
+    #[rstest]
+    fn scout_until_next_turn(mut app: App) {
+        let world = app.world_mut();
+        let party = world
+            .spawn((Party::default(), MapPresence::default(), ViewRadius(3)))
+            .id();
+        let scout = GameAction::new_scout(party);
+        assert_eq!(GameActions::can_perform(world, &scout), Ok(()));
+
+        world.get_mut::<ViewRadius>(party).unwrap().0 = 5;
+        world.entity_mut(party).insert(Scouting { bonus: 2 });
+        assert_eq!(
+            GameActions::can_perform(world, &scout),
+            Err(ActionBlocked::AlreadyScouting)
+        );
+
+        world.run_system_once(end_scouting).unwrap();
+        assert_eq!(world.get::<ViewRadius>(party).unwrap().0, 3);
+        assert!(world.get::<Scouting>(party).is_none());
+    }
+
+    #[rstest]
+    fn pay_and_refund_cost(mut app: App) {
+        let mut inventory = Inventory::default();
+        inventory.add_item(Inventory::SUPPLY, 1);
+        let party = app
+            .world_mut()
+            .spawn((inventory, ActionPoints::new(2)))
+            .id();
+        let cost = ResolvedCost {
+            action_points: 2,
+            items: smallvec![(Inventory::SUPPLY, 1)],
+        };
+
+        let world = app.world_mut();
+        assert_eq!(cost.pay(world, party), Ok(()));
+        assert_eq!(world.get::<ActionPoints>(party).unwrap().current, 0);
+        assert_eq!(
+            world
+                .get::<Inventory>(party)
+                .unwrap()
+                .count_item(Inventory::SUPPLY),
+            0
+        );
+        assert_eq!(cost.check(world, party), Err(ActionBlocked::NoActionPoints));
+
+        cost.refund(world, party);
+        assert_eq!(world.get::<ActionPoints>(party).unwrap().current, 2);
+        assert_eq!(
+            world
+                .get::<Inventory>(party)
+                .unwrap()
+                .count_item(Inventory::SUPPLY),
+            1
+        );
+    }
 }
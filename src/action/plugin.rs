@@ -1,9 +1,10 @@
-use super::{component::*, event::*, queue::*, system::*};
+use super::{check::*, component::*, cost::*, queue::*, system::*, undo::*};
+use crate::inventory::Inventory;
 use crate::{
     actor::SlideEvent,
     error,
     scene::SceneState,
-    turn::{set_player_turn, TurnState},
+    turn::{set_player_turn, TurnSet, TurnState},
 };
 use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
 
@@ -15,68 +16,122 @@ pub struct ActionPlugin;
 impl Plugin for ActionPlugin {
     fn build(&self, app: &mut App) {
         let game_action_systems = GameActions::builder(app.world_mut())
-            .register_action(GameActionType::Move, ActionCost::World, handle_move)
+            .register_action(
+                GameActionType::Move,
+                ActionCost::terrain(),
+                handle_move,
+                can_move,
+            )
             .register_action(
                 GameActionType::MakeCamp,
-                ActionCost::World,
+                ActionCost::action_points(1).with_item(Inventory::SUPPLY, 1),
                 handle_make_camp,
+                can_make_camp,
             )
             .register_action(
                 GameActionType::BreakCamp,
-                ActionCost::World,
+                ActionCost::action_points(1),
                 handle_break_camp,
+                can_break_camp,
             )
             .register_action(
                 GameActionType::EnterCamp,
-                ActionCost::Free,
+                ActionCost::free(),
                 handle_enter_camp,
+                can_enter_camp,
             )
             .register_action(
                 GameActionType::SplitParty,
-                ActionCost::Free,
+                ActionCost::free(),
                 handle_split_party,
+                can_split_party,
             )
             .register_action(
                 GameActionType::MergeParty,
-                ActionCost::Free,
+                ActionCost::free(),
                 handle_merge_party,
+                can_merge_party,
             )
             .register_action(
                 GameActionType::CreatePartyFromCamp,
-                ActionCost::Free,
+                ActionCost::free(),
                 handle_create_party_from_camp,
+                can_create_party_from_camp,
             )
             .register_action(
                 GameActionType::CollectCrystals,
-                ActionCost::World,
+                ActionCost::action_points(1),
                 handle_collect_crystals,
+                can_collect_crystals,
             )
             .register_action(
                 GameActionType::OpenPortal,
-                ActionCost::World,
+                ActionCost::action_points(1),
                 handle_open_portal,
+                can_open_portal,
             )
             .register_action(
                 GameActionType::EnterPortal,
-                ActionCost::World,
+                ActionCost::action_points(1),
                 handle_enter_portal,
+                can_enter_portal,
+            )
+            .register_action(
+                GameActionType::Forage,
+                ActionCost::action_points(1),
+                handle_forage,
+                can_forage,
+            )
+            .register_action(
+                GameActionType::Hunt,
+                ActionCost::action_points(1),
+                handle_hunt,
+                can_hunt,
+            )
+            .register_action(
+                GameActionType::TransferItems,
+                ActionCost::free(),
+                handle_transfer_items,
+                can_transfer_items,
+            )
+            .register_action(
+                GameActionType::Attack,
+                ActionCost::action_points(1),
+                handle_attack,
+                can_attack,
+            )
+            .register_action(
+                GameActionType::Retreat,
+                ActionCost::free(),
+                handle_retreat,
+                can_retreat,
+            )
+            .register_action(
+                GameActionType::Scout,
+                ActionCost::action_points(1),
+                handle_scout,
+                can_scout,
             )
             .build();
         let game_action_follow_up_system =
             GameActionFollowUpSystem(app.world_mut().register_system(follow_up_action));
         app.insert_resource(GameActionQueue::default())
+            .init_resource::<UndoStack>()
             .insert_resource(game_action_follow_up_system)
             .insert_resource(game_action_systems)
-            .add_event::<ActionPointsConsumed>()
             .init_schedule(ActionUpdate)
             .register_type::<ActionPoints>()
+            .register_type::<Scouting>()
             .add_observer(update_action_points_on_member_added)
             .add_observer(update_action_points_on_member_removed)
-            .add_observer(propagate_action_points_consumed)
             .add_systems(
                 OnEnter(TurnState::Player),
-                (reset_action_points, reset_group_action_points)
-                    .chain()
+                (
+                    (reset_action_points, reset_group_action_points).chain(),
+                    clear_undo_stack,
+                    end_scouting,
+                )
+                    .in_set(TurnSet::Setup)
                     .run_if(in_state(SceneState::Active)),
             )
             .add_systems(
@@ -86,6 +141,7 @@ impl Plugin for ActionPlugin {
                         apply_action.map(error::warn).run_if(has_ready_action),
                         handle_slide_stopped.run_if(on_event::<SlideEvent>),
                         resolve_action.map(error::warn).run_if(has_resolved_action),
+                        (clear_undo_stack_on_combat, clear_undo_stack_on_reveal),
                     )
                         .chain(),
                     set_player_turn
@@ -1,10 +1,16 @@
-use crate::{combat::Combat, ExplError};
-use bevy::{ecs::system::SystemId, prelude::*};
+use super::{check::ActionBlocked, cost::ActionCost};
+use crate::{combat::Combat, inventory::Item, ExplError};
+use bevy::{
+    ecs::system::{ReadOnlySystem, SystemId},
+    prelude::*,
+};
 use enum_map::{Enum, EnumMap};
+use expl_codex::Id;
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::collections::VecDeque;
 
-#[derive(Clone, Copy, Debug, Enum)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Enum)]
 pub enum GameActionType {
     Move,
     MakeCamp,
@@ -16,6 +22,19 @@ pub enum GameActionType {
     CollectCrystals,
     OpenPortal,
     EnterPortal,
+    Forage,
+    Hunt,
+    TransferItems,
+    Attack,
+    Retreat,
+    Scout,
+}
+
+impl GameActionType {
+    /// Whether the action can be applied while combat is going on.
+    pub fn allowed_in_combat(&self) -> bool {
+        matches!(self, GameActionType::Retreat)
+    }
 }
 
 #[derive(Default, Debug, Hash, PartialEq, Eq, Clone)]
@@ -27,15 +46,53 @@ pub enum GameActionStatus {
 }
 
 pub type GameActionResult = Result<GameActionStatus, ExplError>;
+pub type ActionCheckResult = Result<(), ActionBlocked>;
 
 #[derive(Clone, Debug)]
 pub struct GameAction {
     pub(super) action_type: GameActionType,
     pub(super) source: Entity,
     pub(super) targets: SmallVec<[Entity; 8]>,
+    pub(super) items: SmallVec<[(Id<Item>, u32); 2]>,
 }
 
 impl GameAction {
+    pub fn new<Targets>(action_type: GameActionType, source: Entity, targets: Targets) -> Self
+    where
+        Targets: IntoIterator<Item = Entity>,
+    {
+        Self {
+            action_type,
+            source,
+            targets: targets.into_iter().collect(),
+            items: SmallVec::default(),
+        }
+    }
+
+    pub fn action_type(&self) -> GameActionType {
+        self.action_type
+    }
+
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+
+    pub fn targets(&self) -> &[Entity] {
+        &self.targets
+    }
+
+    pub fn items(&self) -> &[(Id<Item>, u32)] {
+        &self.items
+    }
+
+    pub fn with_items<Items>(mut self, items: Items) -> Self
+    where
+        Items: IntoIterator<Item = (Id<Item>, u32)>,
+    {
+        self.items = items.into_iter().collect();
+        self
+    }
+
     pub fn target(&self) -> Result<Entity, ExplError> {
         self.targets
             .first()
@@ -48,6 +105,7 @@ impl GameAction {
             action_type: GameActionType::Move,
             source,
             targets: SmallVec::from_slice(&[dest]),
+            items: SmallVec::default(),
         }
     }
 
@@ -56,6 +114,7 @@ impl GameAction {
             action_type: GameActionType::MakeCamp,
             source,
             targets: SmallVec::default(),
+            items: SmallVec::default(),
         }
     }
 
@@ -64,6 +123,7 @@ impl GameAction {
             action_type: GameActionType::BreakCamp,
             source,
             targets: SmallVec::default(),
+            items: SmallVec::default(),
         }
     }
 
@@ -72,6 +132,7 @@ impl GameAction {
             action_type: GameActionType::EnterCamp,
             source,
             targets: SmallVec::from_slice(&[camp]),
+            items: SmallVec::default(),
         }
     }
 
@@ -83,6 +144,7 @@ impl GameAction {
             action_type: GameActionType::SplitParty,
             source,
             targets: characters.into_iter().collect(),
+            items: SmallVec::default(),
         }
     }
 
@@ -94,6 +156,7 @@ impl GameAction {
             action_type: GameActionType::MergeParty,
             source,
             targets: parties.into_iter().collect(),
+            items: SmallVec::default(),
         }
     }
 
@@ -105,6 +168,7 @@ impl GameAction {
             action_type: GameActionType::CreatePartyFromCamp,
             source,
             targets: characters.into_iter().collect(),
+            items: SmallVec::default(),
         }
     }
 
@@ -113,6 +177,7 @@ impl GameAction {
             action_type: GameActionType::CollectCrystals,
             source,
             targets: SmallVec::default(),
+            items: SmallVec::default(),
         }
     }
 
@@ -121,6 +186,7 @@ impl GameAction {
             action_type: GameActionType::OpenPortal,
             source,
             targets: SmallVec::default(),
+            items: SmallVec::default(),
         }
     }
 
@@ -129,8 +195,46 @@ impl GameAction {
             action_type: GameActionType::EnterPortal,
             source,
             targets: SmallVec::default(),
+            items: SmallVec::default(),
         }
     }
+
+    pub fn new_forage(source: Entity) -> Self {
+        Self {
+            action_type: GameActionType::Forage,
+            source,
+            targets: SmallVec::default(),
+            items: SmallVec::default(),
+        }
+    }
+
+    pub fn new_hunt(source: Entity) -> Self {
+        Self {
+            action_type: GameActionType::Hunt,
+            source,
+            targets: SmallVec::default(),
+            items: SmallVec::default(),
+        }
+    }
+
+    pub fn new_transfer_items<Items>(source: Entity, target: Entity, items: Items) -> Self
+    where
+        Items: IntoIterator<Item = (Id<Item>, u32)>,
+    {
+        Self::new(GameActionType::TransferItems, source, [target]).with_items(items)
+    }
+
+    pub fn new_attack(source: Entity, zone: Entity) -> Self {
+        Self::new(GameActionType::Attack, source, [zone])
+    }
+
+    pub fn new_retreat(source: Entity, zone: Entity) -> Self {
+        Self::new(GameActionType::Retreat, source, [zone])
+    }
+
+    pub fn new_scout(source: Entity) -> Self {
+        Self::new(GameActionType::Scout, source, [])
+    }
 }
 
 #[derive(Default, Resource)]
@@ -174,14 +278,9 @@ impl GameActionQueue {
     }
 }
 
-#[derive(Clone, Copy)]
-pub enum ActionCost {
-    Free,
-    World,
-}
-
 pub struct GameActionInfo {
     pub(super) system: SystemId<In<GameAction>, GameActionResult>,
+    pub(super) check: SystemId<In<GameAction>, ActionCheckResult>,
     pub(super) action_cost: ActionCost,
 }
 
@@ -196,6 +295,21 @@ impl GameActions {
     pub fn get(&self, action: GameActionType) -> Option<&GameActionInfo> {
         self.0[action].as_ref()
     }
+
+    /// Check whether `action` would be performed, without applying it.
+    pub fn can_perform(world: &mut World, action: &GameAction) -> ActionCheckResult {
+        let Some((check, cost)) = world
+            .get_resource::<GameActions>()
+            .and_then(|game_actions| game_actions.get(action.action_type))
+            .map(|info| (info.check, info.action_cost.resolve(world, action)))
+        else {
+            return Err(ActionBlocked::InvalidSource);
+        };
+        cost.check(world, action.source)?;
+        world
+            .run_system_with(check, action.clone())
+            .unwrap_or(Err(ActionBlocked::InvalidSource))
+    }
 }
 
 pub struct GameActionSystemsBuilder<'a> {
@@ -211,18 +325,24 @@ impl<'a> GameActionSystemsBuilder<'a> {
         }
     }
 
-    pub fn register_action<F, Marker>(
+    /// Register the system that applies `action`, what it costs and the read-only system that
+    /// checks whether it can be applied.
+    pub fn register_action<F, C, Marker, CheckMarker>(
         mut self,
         action: GameActionType,
-        action_point_cost: ActionCost,
+        action_cost: ActionCost,
         f: F,
+        check: C,
     ) -> Self
     where
         F: IntoSystem<In<GameAction>, GameActionResult, Marker> + 'static,
+        C: IntoSystem<In<GameAction>, ActionCheckResult, CheckMarker> + 'static,
+        C::System: ReadOnlySystem,
     {
         self.enum_map[action] = Some(GameActionInfo {
             system: self.world.register_system(f),
-            action_cost: action_point_cost,
+            check: self.world.register_system(check),
+            action_cost,
         });
         self
     }
@@ -240,8 +360,9 @@ pub fn has_ready_action(
     combat_query: Query<&Combat>,
 ) -> bool {
     !game_action_queue.is_waiting()
-        && game_action_queue.current().is_some()
-        && combat_query.is_empty()
+        && game_action_queue
+            .current()
+            .is_some_and(|action| combat_query.is_empty() || action.action_type.allowed_in_combat())
 }
 
 pub fn has_resolved_action(game_action_queue: Res<GameActionQueue>) -> bool {
@@ -1,20 +1,32 @@
-use super::{component::ActionPoints, event::*, plugin::ActionUpdate, queue::*};
+use super::{
+    check::SupplyYield,
+    component::{ActionPoints, Scouting},
+    plugin::ActionUpdate,
+    queue::*,
+    undo::UndoStack,
+};
 use crate::{
     actor::{
-        ActorCodex, ActorParams, GroupCommandsExt, MemberAdded, MemberRemoved, Members, Party,
-        PartyBundle, Slide, SlideEvent,
+        ActorCodex, ActorParams, Enemy, GroupCommandsExt, MemberAdded, MemberRemoved, Members,
+        Party, PartyBundle, Slide, SlideEvent,
     },
-    combat::CombatEvent,
+    combat::{Combat, CombatEvent, InitiateCombat},
+    creature::{Attack, Health},
     inventory::Inventory,
-    path::PathGuided,
+    journal::Journal,
+    map_generator::GameRng,
+    path::{AutoExplore, Frontier, PathGuided},
     role::RoleCommandsExt,
     scene::save,
     structure::{Camp, CampBundle, Portal, SafeHaven, StructureCodex, StructureParams},
-    terrain::{CrystalDeposit, HeightQuery, TerrainCodex, TerrainId},
+    terrain::{CrystalDeposit, HeightQuery, SupplySource, TerrainCodex, TerrainId},
     ExplError,
 };
 use bevy::prelude::*;
-use expl_map::{Fog, MapCommandsExt, MapPosition, MapPresence, PresenceLayer, ZoneLayer};
+use expl_map::{
+    Fog, MapCommandsExt, MapPosition, MapPresence, OnMap, PresenceLayer, ViewRadius, ZoneLayer,
+};
+use rand::Rng;
 use smallvec::SmallVec;
 
 pub fn apply_action(world: &mut World) -> Result<(), ExplError> {
@@ -34,23 +46,26 @@ pub fn apply_action(world: &mut World) -> Result<(), ExplError> {
 
     let action = action.clone();
     let action_system = action_info.system;
-    let action_point_cost = action_info.action_cost;
-
-    match action_point_cost {
-        ActionCost::Free => {}
-        ActionCost::World => {
-            let mut source = world.get_entity_mut(action.source).unwrap();
-            if let Some(mut action_points) = source.get_mut::<ActionPoints>() {
-                if let Err(e) = action_points.consume() {
-                    let mut queue = world
-                        .get_resource_mut::<GameActionQueue>()
-                        .ok_or(ExplError::ResourceMissing)?;
-                    queue.ready();
-                    return Err(e);
-                }
-                world.trigger_targets(ActionPointsConsumed, action.source);
-            }
-        }
+    let cost = action_info.action_cost.resolve(world, &action);
+
+    if let Err(blocked) = GameActions::can_perform(world, &action) {
+        let mut queue = world
+            .get_resource_mut::<GameActionQueue>()
+            .ok_or(ExplError::ResourceMissing)?;
+        queue.ready();
+        return Err(blocked.into());
+    }
+
+    UndoStack::record(world, &action);
+    Journal::record(world, &action);
+
+    if let Err(blocked) = cost.pay(world, action.source) {
+        discard_pending(world);
+        let mut queue = world
+            .get_resource_mut::<GameActionQueue>()
+            .ok_or(ExplError::ResourceMissing)?;
+        queue.ready();
+        return Err(blocked.into());
     }
 
     let result = world.run_system_with(action_system, action.clone())?;
@@ -68,6 +83,8 @@ pub fn apply_action(world: &mut World) -> Result<(), ExplError> {
         }
         Ok(GameActionStatus::Resolved) => {}
         Err(e) => {
+            cost.refund(world, action.source);
+            discard_pending(world);
             let mut queue = world
                 .get_resource_mut::<GameActionQueue>()
                 .ok_or(ExplError::ResourceMissing)?;
@@ -77,6 +94,7 @@ pub fn apply_action(world: &mut World) -> Result<(), ExplError> {
     }
 
     world.run_schedule(ActionUpdate);
+    commit_pending(world);
 
     let follow_up_system = world
         .get_resource::<GameActionFollowUpSystem>()
@@ -99,8 +117,27 @@ pub fn apply_action(world: &mut World) -> Result<(), ExplError> {
     Ok(())
 }
 
+fn commit_pending(world: &mut World) {
+    if let Some(mut undo_stack) = world.get_resource_mut::<UndoStack>() {
+        undo_stack.commit();
+    }
+    if let Some(mut journal) = world.get_resource_mut::<Journal>() {
+        journal.commit();
+    }
+}
+
+fn discard_pending(world: &mut World) {
+    if let Some(mut undo_stack) = world.get_resource_mut::<UndoStack>() {
+        undo_stack.discard();
+    }
+    if let Some(mut journal) = world.get_resource_mut::<Journal>() {
+        journal.discard();
+    }
+}
+
 pub fn resolve_action(world: &mut World) -> Result<(), ExplError> {
     world.run_schedule(ActionUpdate);
+    commit_pending(world);
     let queue = world
         .get_resource::<GameActionQueue>()
         .ok_or(ExplError::ResourceMissing)?;
@@ -132,10 +169,22 @@ pub fn resolve_action(world: &mut World) -> Result<(), ExplError> {
 
 pub fn follow_up_action(
     In(action): In<GameAction>,
+    mut commands: Commands,
     mut combat_events: EventReader<CombatEvent>,
-    mut path_guided_query: Query<(&ActionPoints, &mut PathGuided)>,
+    mut path_guided_query: Query<(
+        &ActionPoints,
+        &mut PathGuided,
+        &MapPresence,
+        Has<AutoExplore>,
+    )>,
+    frontier: Frontier,
 ) -> Option<GameAction> {
-    let Ok((party_action_points, mut pathguided)) = path_guided_query.get_mut(action.source) else {
+    if action.action_type != GameActionType::Move {
+        return None;
+    }
+    let Ok((party_action_points, mut pathguided, presence, auto_explore)) =
+        path_guided_query.get_mut(action.source)
+    else {
         return None;
     };
 
@@ -145,14 +194,24 @@ pub fn follow_up_action(
         return None;
     }
 
-    // Keep moving if a path is set
-    if party_action_points.current > 0 {
-        if let Some(next) = pathguided.next() {
-            return Some(GameAction::new_move(action.source, *next));
+    if party_action_points.current == 0 {
+        return None;
+    }
+
+    // Head for the next frontier when exploring
+    if auto_explore {
+        let next = frontier.next_step(presence.position, &mut pathguided);
+        if next.is_none() {
+            info!("Nothing left to explore for {}", action.source);
+            commands.entity(action.source).remove::<AutoExplore>();
         }
+        return next.map(|next| GameAction::new_move(action.source, next));
     }
 
-    None
+    // Keep moving if a path is set
+    pathguided
+        .next()
+        .map(|&next| GameAction::new_move(action.source, next))
 }
 
 pub fn reset_action_points(mut action_points_query: Query<&mut ActionPoints, Without<Members>>) {
@@ -207,33 +266,22 @@ pub fn update_action_points_on_member_removed(
     }
 }
 
-pub fn propagate_action_points_consumed(
-    trigger: Trigger<ActionPointsConsumed>,
-    group_query: Query<&Members>,
-    mut action_points_query: Query<&mut ActionPoints>,
-) {
-    let Ok(members) = group_query.get(trigger.target()) else {
-        return;
-    };
-    let mut iter = action_points_query.iter_many_mut(&members.0);
-    while let Some(mut action_points) = iter.fetch_next() {
-        action_points.consume().unwrap();
-    }
-}
-
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_arguments)]
 pub fn handle_move(
     In(action): In<GameAction>,
     mut commands: Commands,
-    mut party_query: Query<(&mut Slide, &mut Transform, &MapPresence), Without<MapPosition>>,
+    mut party_query: Query<
+        (&mut Slide, &mut Transform, &MapPresence, &OnMap),
+        Without<MapPosition>,
+    >,
     zone_layer_query: Query<(Entity, &ZoneLayer)>,
     map_position_query: Query<(&MapPosition, &Transform)>,
     fog_query: Query<&Fog>,
     height_query: HeightQuery,
 ) -> GameActionResult {
-    let (mut slide, mut transform, presence) = party_query.get_mut(action.source)?;
-    let (map_entity, zone_layer) = zone_layer_query.single()?;
+    let (mut slide, mut transform, presence, on_map) = party_query.get_mut(action.source)?;
+    let (map_entity, zone_layer) = zone_layer_query.get(on_map.0)?;
     let (next_position, next_transform) = map_position_query.get(action.target()?)?;
     let source_fog = zone_layer
         .get(presence.position)
@@ -260,16 +308,16 @@ pub fn handle_slide_stopped(
     mut commands: Commands,
     mut events: EventReader<SlideEvent>,
     mut queue: ResMut<GameActionQueue>,
-    map_query: Query<Entity, With<PresenceLayer>>,
+    on_map_query: Query<&OnMap>,
     map_position_query: Query<&MapPosition>,
 ) {
-    let Ok(map_entity) = map_query.single() else {
-        return;
-    };
     for _ in events.read() {
         let Some(action) = queue.current() else {
             return;
         };
+        let Ok(&OnMap(map_entity)) = on_map_query.get(action.source) else {
+            return;
+        };
         let Ok(target) = action.target() else {
             return;
         };
@@ -291,7 +339,7 @@ pub fn handle_make_camp(
     mut structure_params: StructureParams,
     map_query: Query<(Entity, &ZoneLayer, &PresenceLayer)>,
     terrain_query: Query<&TerrainId>,
-    mut party_query: Query<(&mut Inventory, &Members, &MapPresence), With<Party>>,
+    party_query: Query<(&Inventory, &Members, &MapPresence, &OnMap), With<Party>>,
     camp_query: Query<&Camp>,
     terrain_codex: TerrainCodex,
     structure_codex: StructureCodex,
@@ -299,8 +347,8 @@ pub fn handle_make_camp(
     let terrain_codex = terrain_codex.get()?;
     let structure_codex = structure_codex.get()?;
 
-    let (mut party_inventory, members, presence) = party_query.get_mut(action.source)?;
-    let (map_entity, zone_layer, presence_layer) = map_query.single()?;
+    let (party_inventory, members, presence, on_map) = party_query.get(action.source)?;
+    let (map_entity, zone_layer, presence_layer) = map_query.get(on_map.0)?;
     let terrain_id = zone_layer
         .get(presence.position)
         .ok_or(ExplError::OutOfBounds)
@@ -319,8 +367,6 @@ pub fn handle_make_camp(
         return Err(ExplError::InvalidLocation("existing camp".to_string()));
     }
 
-    party_inventory.take_item(Inventory::SUPPLY, 1)?;
-
     let camp_inventory: Inventory = party_inventory.clone();
 
     info!("Spawning camp at {}", position);
@@ -341,12 +387,12 @@ pub fn handle_make_camp(
 pub fn handle_break_camp(
     In(action): In<GameAction>,
     mut commands: Commands,
-    mut party_query: Query<(&mut Inventory, &MapPresence), With<Party>>,
+    mut party_query: Query<(&mut Inventory, &MapPresence, &OnMap), With<Party>>,
     map_query: Query<(Entity, &PresenceLayer)>,
     camp_query: Query<(Entity, &Members), With<Camp>>,
 ) -> GameActionResult {
-    let (mut inventory, presence) = party_query.get_mut(action.source)?;
-    let (map_entity, presence_layer) = map_query.single()?;
+    let (mut inventory, presence, on_map) = party_query.get_mut(action.source)?;
+    let (map_entity, presence_layer) = map_query.get(on_map.0)?;
     let (camp_entity, members) = camp_query
         .iter_many(presence_layer.presence(presence.position))
         .next()
@@ -381,8 +427,7 @@ pub fn handle_create_party_from_camp(
     In(action): In<GameAction>,
     mut commands: Commands,
     mut party_params: ActorParams,
-    mut camp_query: Query<(&mut Inventory, &MapPresence), With<Camp>>,
-    map_query: Query<Entity, With<PresenceLayer>>,
+    mut camp_query: Query<(&mut Inventory, &MapPresence, &OnMap), With<Camp>>,
     actor_codex: ActorCodex,
 ) -> GameActionResult {
     let actor_codex = actor_codex.get()?;
@@ -390,8 +435,7 @@ pub fn handle_create_party_from_camp(
         "Creating party at camp {:?} {:?}",
         action.source, action.targets
     );
-    let (mut camp_inventory, presence) = camp_query.get_mut(action.source)?;
-    let map_entity = map_query.single()?;
+    let (mut camp_inventory, presence, &OnMap(map_entity)) = camp_query.get_mut(action.source)?;
 
     let new_supplies = camp_inventory.take_item(Inventory::SUPPLY, 1).unwrap_or(0);
 
@@ -415,13 +459,12 @@ pub fn handle_split_party(
     In(action): In<GameAction>,
     mut commands: Commands,
     mut party_params: ActorParams,
-    mut party_query: Query<(&mut Inventory, &Members, &MapPresence), With<Party>>,
-    map_query: Query<Entity, With<PresenceLayer>>,
+    mut party_query: Query<(&mut Inventory, &Members, &MapPresence, &OnMap), With<Party>>,
     actor_codex: ActorCodex,
 ) -> GameActionResult {
     let actor_codex = actor_codex.get()?;
-    let (mut party_inventory, members, presence) = party_query.get_mut(action.source)?;
-    let map_entity = map_query.single()?;
+    let (mut party_inventory, members, presence, &OnMap(map_entity)) =
+        party_query.get_mut(action.source)?;
     if members.len() == action.targets.len() {
         return Err(ExplError::InvalidPartySplit);
     }
@@ -470,11 +513,11 @@ pub fn handle_merge_party(
 pub fn handle_collect_crystals(
     In(action): In<GameAction>,
     map_query: Query<&ZoneLayer>,
-    mut party_query: Query<(&mut Inventory, &MapPresence), With<Party>>,
+    mut party_query: Query<(&mut Inventory, &MapPresence, &OnMap), With<Party>>,
     mut crystal_deposit_query: Query<&mut CrystalDeposit>,
 ) -> GameActionResult {
-    let (mut inventory, presence) = party_query.get_mut(action.source)?;
-    let zone_layer = map_query.single()?;
+    let (mut inventory, presence, on_map) = party_query.get_mut(action.source)?;
+    let zone_layer = map_query.get(on_map.0)?;
 
     let mut crystal_deposit = zone_layer
         .get(presence.position)
@@ -488,12 +531,12 @@ pub fn handle_collect_crystals(
 
 pub fn handle_open_portal(
     In(action): In<GameAction>,
-    party_query: Query<&MapPresence, With<Party>>,
+    party_query: Query<(&MapPresence, &OnMap), With<Party>>,
     map_query: Query<&PresenceLayer>,
     mut portal_query: Query<&mut Portal>,
 ) -> GameActionResult {
-    let presence = party_query.get(action.source)?;
-    let presence_layer = map_query.single()?;
+    let (presence, on_map) = party_query.get(action.source)?;
+    let presence_layer = map_query.get(on_map.0)?;
 
     let mut portal_iter = portal_query.iter_many_mut(presence_layer.presence(presence.position));
     let mut portal = portal_iter
@@ -513,11 +556,11 @@ pub fn handle_enter_portal(
     mut commands: Commands,
     map_query: Query<&PresenceLayer>,
     portal_query: Query<&Portal>,
-    mut party_query: Query<(&MapPresence, &Members, &mut Inventory), With<Party>>,
+    mut party_query: Query<(&MapPresence, &OnMap, &Members, &mut Inventory), With<Party>>,
     mut safe_haven_query: Query<(Entity, &mut Inventory), (With<SafeHaven>, Without<Party>)>,
 ) -> GameActionResult {
-    let (presence, members, mut party_inventory) = party_query.get_mut(action.source)?;
-    let presence_layer = map_query.single()?;
+    let (presence, on_map, members, mut party_inventory) = party_query.get_mut(action.source)?;
+    let presence_layer = map_query.get(on_map.0)?;
 
     let portal = portal_query
         .iter_many(presence_layer.presence(presence.position))
@@ -535,3 +578,158 @@ pub fn handle_enter_portal(
 
     Ok(GameActionStatus::Resolved)
 }
+
+pub fn handle_transfer_items(
+    In(action): In<GameAction>,
+    mut inventory_query: Query<&mut Inventory>,
+) -> GameActionResult {
+    let [mut source, mut target] =
+        inventory_query.get_many_mut([action.source, action.target()?])?;
+    for &(item_id, count) in &action.items {
+        let count = source.take_item(item_id, count)?;
+        target.add_item(item_id, count);
+    }
+    Ok(GameActionStatus::Resolved)
+}
+
+fn gather_supplies(
+    source: SupplySource,
+    action: &GameAction,
+    commands: &mut Commands,
+    supply_yield: &SupplyYield,
+    inventory_query: &mut Query<&mut Inventory, With<Party>>,
+) -> GameActionResult {
+    let (zone, amount, depletion) = supply_yield.get(action.source, source)?;
+    let mut inventory = inventory_query.get_mut(action.source)?;
+    info!("Gathered {} supplies by {:?}", amount, source);
+    inventory.add_item(Inventory::SUPPLY, amount);
+    commands.entity(zone).insert(depletion.deplete(source));
+    Ok(GameActionStatus::Resolved)
+}
+
+pub fn handle_forage(
+    In(action): In<GameAction>,
+    mut commands: Commands,
+    supply_yield: SupplyYield,
+    mut inventory_query: Query<&mut Inventory, With<Party>>,
+) -> GameActionResult {
+    gather_supplies(
+        SupplySource::Forage,
+        &action,
+        &mut commands,
+        &supply_yield,
+        &mut inventory_query,
+    )
+}
+
+pub fn handle_hunt(
+    In(action): In<GameAction>,
+    mut commands: Commands,
+    supply_yield: SupplyYield,
+    mut inventory_query: Query<&mut Inventory, With<Party>>,
+) -> GameActionResult {
+    gather_supplies(
+        SupplySource::Hunt,
+        &action,
+        &mut commands,
+        &supply_yield,
+        &mut inventory_query,
+    )
+}
+
+pub fn handle_attack(
+    In(action): In<GameAction>,
+    mut commands: Commands,
+    party_query: Query<&OnMap, With<Party>>,
+    zone_query: Query<&MapPosition>,
+) -> GameActionResult {
+    let &OnMap(map) = party_query.get(action.source)?;
+    let &MapPosition(position) = zone_query.get(action.target()?)?;
+    commands.trigger(InitiateCombat {
+        party: action.source,
+        map,
+        position,
+    });
+    Ok(GameActionStatus::Resolved)
+}
+
+/// Chance that the enemies keep a retreating party from getting away.
+const RETREAT_FAILURE_CHANCE: f64 = 0.25;
+
+pub fn handle_retreat(
+    In(action): In<GameAction>,
+    mut combat_events: EventWriter<CombatEvent>,
+    mut party_query: Query<(&Members, &mut Slide, &Transform), Without<MapPosition>>,
+    zone_query: Query<&Transform, With<MapPosition>>,
+    mut combat_query: Query<(Entity, &mut Combat)>,
+    attack_query: Query<&Attack, With<Enemy>>,
+    mut health_query: Query<&mut Health, Without<Enemy>>,
+    mut rng: ResMut<GameRng>,
+) -> GameActionResult {
+    let (members, mut slide, transform) = party_query.get_mut(action.source)?;
+    let (combat_entity, mut combat) = combat_query
+        .iter_mut()
+        .find(|(_, combat)| members.iter().any(|&member| combat.contains(member)))
+        .ok_or_else(|| ExplError::InvalidLocation("not in combat".to_string()))?;
+    let next_transform = zone_query.get(action.target()?)?;
+
+    // Every enemy gets a free attack on the retreating party.
+    for attack in attack_query.iter_many(combat.combatants()) {
+        let living: SmallVec<[Entity; 8]> = members
+            .iter()
+            .copied()
+            .filter(|&member| health_query.get(member).is_ok_and(|h| h.current > 0))
+            .collect();
+        if living.is_empty() {
+            break;
+        }
+        let mut health = health_query.get_mut(living[rng.gen_range(0..living.len())])?;
+        let damage = rng.gen_range(attack.range()).min(health.current);
+        health.current -= damage;
+        combat_events.write(CombatEvent::FriendDamage(combat_entity, damage));
+    }
+
+    if rng.gen_bool(RETREAT_FAILURE_CHANCE) {
+        info!("Failed to retreat from combat at {}", combat.position());
+        return Ok(GameActionStatus::Resolved);
+    }
+
+    combat.withdraw(&members[..]);
+    slide.start = transform.translation;
+    slide.end = next_transform.translation;
+    slide.progress = 0.0;
+    Ok(GameActionStatus::Waiting)
+}
+
+/// View radius that scouting adds on top of the elevation of the zone scouted from.
+const SCOUT_VIEW_BONUS: u32 = 2;
+
+pub fn handle_scout(
+    In(action): In<GameAction>,
+    mut commands: Commands,
+    mut party_query: Query<(&MapPresence, &OnMap, &mut ViewRadius), With<Party>>,
+    zone_layer_query: Query<&ZoneLayer>,
+    terrain_query: Query<&TerrainId>,
+    terrain_codex: TerrainCodex,
+) -> GameActionResult {
+    let (presence, &OnMap(map), mut view_radius) = party_query.get_mut(action.source)?;
+    let &zone = zone_layer_query
+        .get(map)?
+        .get(presence.position)
+        .ok_or(ExplError::OutOfBounds)?;
+    let terrain_id = terrain_query.get(zone)?;
+    let bonus = SCOUT_VIEW_BONUS + terrain_codex.get()?[terrain_id].elevation;
+    view_radius.0 += bonus;
+    commands.entity(action.source).insert(Scouting { bonus });
+    Ok(GameActionStatus::Resolved)
+}
+
+pub fn end_scouting(
+    mut commands: Commands,
+    mut scouting_query: Query<(Entity, &Scouting, &mut ViewRadius)>,
+) {
+    for (entity, scouting, mut view_radius) in &mut scouting_query {
+        view_radius.0 = view_radius.0.saturating_sub(scouting.bonus);
+        commands.entity(entity).remove::<Scouting>();
+    }
+}
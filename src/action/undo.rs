@@ -0,0 +1,236 @@
+use super::{component::ActionPoints, queue::*};
+use crate::{
+    actor::Members, combat::Combat, journal::Journal, path::PathGuided, terrain::HeightQuery,
+};
+use bevy::prelude::*;
+use expl_map::{Fog, HexCoord, MapCommandsExt, MapPosition, MapPresence, OnMap, ZoneLayer};
+use smallvec::SmallVec;
+
+/// The state that a move changed, enough to put the party back where it was.
+#[derive(Clone, Debug)]
+pub(super) struct UndoEntry {
+    source: Entity,
+    map: Entity,
+    position: HexCoord,
+    action_points: SmallVec<[(Entity, u16); 8]>,
+}
+
+impl UndoEntry {
+    fn snapshot(world: &World, source: Entity) -> Option<Self> {
+        let entity = world.get_entity(source).ok()?;
+        let &OnMap(map) = entity.get::<OnMap>()?;
+        let position = entity.get::<MapPresence>()?.position;
+        let members = entity
+            .get::<Members>()
+            .map_or(&[][..], |members| &members[..]);
+        let action_points = std::iter::once(source)
+            .chain(members.iter().copied())
+            .filter_map(|e| Some((e, world.get::<ActionPoints>(e)?.current)))
+            .collect();
+        Some(Self {
+            source,
+            map,
+            position,
+            action_points,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum PendingUndo {
+    Reversible(UndoEntry),
+    Irreversible,
+}
+
+/// Resolved actions of the current player turn that can still be undone.
+///
+/// Only moves are recorded, any other action clears the stack as do moves that explore new zones
+/// or lead to combat.
+#[derive(Resource, Default, Debug)]
+pub struct UndoStack {
+    entries: Vec<UndoEntry>,
+    pending: Option<PendingUndo>,
+    explored: usize,
+}
+
+impl UndoStack {
+    /// Remember the state before `action` is applied.
+    pub(super) fn record(world: &mut World, action: &GameAction) {
+        let pending = match action.action_type {
+            GameActionType::Move => UndoEntry::snapshot(world, action.source)
+                .map_or(PendingUndo::Irreversible, PendingUndo::Reversible),
+            _ => PendingUndo::Irreversible,
+        };
+        if let Some(mut undo_stack) = world.get_resource_mut::<UndoStack>() {
+            undo_stack.pending = Some(pending);
+        }
+    }
+
+    /// Apply what was recorded for the action that was just resolved.
+    pub(super) fn commit(&mut self) {
+        match self.pending.take() {
+            Some(PendingUndo::Reversible(entry)) => self.entries.push(entry),
+            Some(PendingUndo::Irreversible) => self.entries.clear(),
+            None => {}
+        }
+    }
+
+    /// Forget what was recorded for an action that failed.
+    pub(super) fn discard(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending = None;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+pub fn clear_undo_stack(mut undo_stack: ResMut<UndoStack>) {
+    undo_stack.clear();
+}
+
+pub fn clear_undo_stack_on_combat(
+    mut undo_stack: ResMut<UndoStack>,
+    combat_query: Query<(), Added<Combat>>,
+) {
+    if !combat_query.is_empty() {
+        undo_stack.clear();
+    }
+}
+
+pub fn clear_undo_stack_on_reveal(
+    mut undo_stack: ResMut<UndoStack>,
+    changed_fog_query: Query<(), (Changed<Fog>, With<MapPosition>)>,
+    fog_query: Query<&Fog, With<MapPosition>>,
+) {
+    if changed_fog_query.is_empty() {
+        return;
+    }
+    let explored = fog_query.iter().filter(|fog| fog.explored).count();
+    if explored > undo_stack.explored {
+        undo_stack.clear();
+    }
+    undo_stack.explored = explored;
+}
+
+pub fn can_undo(undo_stack: Res<UndoStack>, game_action_queue: Res<GameActionQueue>) -> bool {
+    !undo_stack.is_empty() && game_action_queue.is_empty()
+}
+
+#[allow(clippy::type_complexity)]
+pub fn undo_last_action(
+    mut commands: Commands,
+    mut undo_stack: ResMut<UndoStack>,
+    mut journal: Option<ResMut<Journal>>,
+    mut action_points_query: Query<&mut ActionPoints>,
+    mut party_query: Query<(&mut Transform, Option<&mut PathGuided>), Without<MapPosition>>,
+    zone_query: Query<&ZoneLayer>,
+    zone_transform_query: Query<&Transform, With<MapPosition>>,
+    height_query: HeightQuery,
+) {
+    let Some(entry) = undo_stack.entries.pop() else {
+        return;
+    };
+    info!("Undoing move of {:?} to {}", entry.source, entry.position);
+    if let Some(journal) = journal.as_mut() {
+        journal.undo();
+    }
+
+    for (entity, current) in entry.action_points {
+        if let Ok(mut action_points) = action_points_query.get_mut(entity) {
+            action_points.current = current;
+        }
+    }
+
+    if let Ok((mut transform, path_guided)) = party_query.get_mut(entry.source) {
+        if let Some(zone_transform) = zone_query
+            .get(entry.map)
+            .ok()
+            .and_then(|zone_layer| zone_layer.get(entry.position))
+            .and_then(|&zone| zone_transform_query.get(zone).ok())
+        {
+            transform.translation = height_query.adjust(zone_transform.translation);
+        }
+        if let Some(mut path_guided) = path_guided {
+            path_guided.path([]);
+        }
+    }
+
+    commands
+        .entity(entry.map)
+        .move_presence(entry.source, entry.position);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn world_with_entry() -> World {
+        let mut world = World::new();
+        let map = world.spawn_empty().id();
+        let party = world
+            .spawn((
+                OnMap(map),
+                MapPresence {
+                    position: HexCoord::ZERO,
+                },
+                ActionPoints::new(2),
+            ))
+            .id();
+        world.init_resource::<UndoStack>();
+        UndoStack::record(&mut world, &GameAction::new_move(party, party));
+        world.resource_mut::<UndoStack>().commit();
+        world
+    }
+
+    #[test]
+    fn record_move() {
+        let world = world_with_entry();
+        let undo_stack = world.resource::<UndoStack>();
+        assert_eq!(undo_stack.entries.len(), 1);
+        assert_eq!(undo_stack.entries[0].action_points[0].1, 2);
+    }
+
+    #[test]
+    fn other_action_clears() {
+        let mut world = world_with_entry();
+        let source = world.resource::<UndoStack>().entries[0].source;
+        UndoStack::record(&mut world, &GameAction::new_make_camp(source));
+        world.resource_mut::<UndoStack>().commit();
+        assert!(world.resource::<UndoStack>().is_empty());
+    }
+
+    #[test]
+    fn failed_action_keeps() {
+        let mut world = world_with_entry();
+        let source = world.resource::<UndoStack>().entries[0].source;
+        UndoStack::record(&mut world, &GameAction::new_make_camp(source));
+        world.resource_mut::<UndoStack>().discard();
+        world.resource_mut::<UndoStack>().commit();
+        assert!(!world.resource::<UndoStack>().is_empty());
+    }
+
+    #[test]
+    fn reveal_clears() {
+        let mut world = world_with_entry();
+        world.spawn((MapPosition(HexCoord::ZERO), Fog::default()));
+        world.run_system_once(clear_undo_stack_on_reveal).unwrap();
+        assert!(!world.resource::<UndoStack>().is_empty());
+
+        world.spawn((
+            MapPosition(HexCoord::new(1, 0)),
+            Fog {
+                visible: true,
+                explored: true,
+            },
+        ));
+        world.run_system_once(clear_undo_stack_on_reveal).unwrap();
+        assert!(world.resource::<UndoStack>().is_empty());
+    }
+}
@@ -1,29 +1,37 @@
+use crate::locale::impl_localized_entry;
 use bevy::{asset::LoadContext, prelude::*};
-use expl_codex::{CodexSource, FromWithLoadContext};
+use expl_codex::{AssetReference, CodexSource, FromWithLoadContext};
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Default, Deserialize)]
-pub(super) struct RawActor {
+#[derive(Clone, Debug, Default, Deserialize, Reflect)]
+pub struct RawActor {
     color: Color,
     outline_color: Color,
+    #[reflect(@AssetReference)]
     mesh: String,
     offset: f32,
     scale: f32,
+    name_key: Option<String>,
+    description_key: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, TypePath)]
+#[derive(Clone, Debug, Default, PartialEq, TypePath)]
 pub struct Actor {
     pub color: Color,
     pub outline_color: Color,
     pub mesh: Handle<Mesh>,
     pub offset: f32,
     pub scale: f32,
+    pub name_key: Option<String>,
+    pub description_key: Option<String>,
 }
 
 impl CodexSource for Actor {
-    const EXTENSION: &'static str = "actor.toml";
+    const EXTENSION: &'static str = "actor";
 }
 
+impl_localized_entry!(Actor, actor_codex);
+
 impl FromWithLoadContext<RawActor> for Actor {
     fn from_with_load_context(raw: RawActor, load_context: &mut LoadContext) -> Self {
         Self {
@@ -32,6 +40,8 @@ impl FromWithLoadContext<RawActor> for Actor {
             mesh: load_context.load(raw.mesh),
             offset: raw.offset,
             scale: raw.scale,
+            name_key: raw.name_key,
+            description_key: raw.description_key,
         }
     }
 }
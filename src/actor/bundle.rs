@@ -11,7 +11,7 @@ use crate::{
 use bevy::prelude::*;
 use bevy_mod_outline::OutlineVolume;
 use expl_codex::{Codex, Id};
-use expl_map::{FogRevealer, HexCoord, MapPresence, ViewRadius};
+use expl_map::{Faction, FogRevealer, HexCoord, MapPresence, RevealerFaction, ViewRadius};
 
 pub type ActorParams<'w, 's> = (ResMut<'w, Assets<StandardMaterial>>, HeightQuery<'w, 's>);
 
@@ -112,6 +112,8 @@ pub struct EnemyBundle {
     presence: MapPresence,
     view_radius: ViewRadius,
     slide: Slide,
+    fog_revealer: FogRevealer,
+    revealer_faction: RevealerFaction,
 }
 
 impl CharacterBundle {
@@ -162,6 +164,7 @@ impl EnemyBundle {
         Self {
             presence,
             view_radius,
+            revealer_faction: RevealerFaction(Faction::ENEMY),
             actor_id: ActorId(actor_id),
             creature: CreatureBundle::new(creature_codex, creature_id),
             ..default()
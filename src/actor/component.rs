@@ -6,7 +6,7 @@ use bevy::{
 use expl_codex::Id;
 use smallvec::SmallVec;
 
-#[derive(Component, Reflect, Default, Deref)]
+#[derive(Component, Reflect, Copy, Clone, Default, Debug, Deref)]
 #[reflect(Component)]
 pub struct ActorId(pub Id<Actor>);
 
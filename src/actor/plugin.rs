@@ -1,10 +1,12 @@
 use super::{asset::*, component::*, event::*, system::*};
 use crate::{
+    assets::CodexAppExt,
     error,
     scene::{SceneSet, SceneState},
 };
 use bevy::prelude::*;
-use expl_codex::{Codex, CodexLoader, Id};
+use expl_codex::Id;
+use expl_map::MapAppExt;
 
 pub struct ActorPlugin;
 
@@ -13,8 +15,7 @@ impl Plugin for ActorPlugin {
         app.add_event::<SlideEvent>()
             .add_event::<MemberAdded>()
             .add_event::<MemberRemoved>()
-            .init_asset::<Codex<Actor>>()
-            .init_asset_loader::<CodexLoader<RawActor, Actor>>()
+            .init_codex::<RawActor, Actor>()
             .register_type::<ActorId>()
             .register_type::<Id<Actor>>()
             .register_type::<Character>()
@@ -23,6 +24,7 @@ impl Plugin for ActorPlugin {
             .register_type::<Group>()
             .register_type::<Party>()
             .register_type::<Slide>()
+            .remember_presences::<ActorId>()
             .add_observer(despawn_empty_party.map(error::warn))
             .add_systems(
                 OnEnter(SceneState::Active),
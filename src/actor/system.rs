@@ -1,7 +1,7 @@
 use super::{bundle::*, component::*, event::*, system_param::*};
 use crate::{role::RoleCommandsExt, terrain::HeightQuery, ExplError};
 use bevy::prelude::*;
-use expl_map::{Fog, MapCommandsExt, MapPosition, MapPresence, PresenceLayer, ZoneLayer};
+use expl_map::{Fog, MapCommandsExt, MapPosition, MapPresence, OnMap, PresenceLayer, ZoneLayer};
 use interpolation::Ease;
 
 #[allow(clippy::type_complexity)]
@@ -78,16 +78,12 @@ pub fn update_enemy_visibility(
 #[allow(clippy::type_complexity)]
 pub fn despawn_empty_party(
     trigger: Trigger<MemberRemoved>,
-    party_query: Query<&Members, (With<Party>, With<MapPresence>)>,
-    map_query: Query<Entity, With<PresenceLayer>>,
+    party_query: Query<(&Members, &OnMap), (With<Party>, With<MapPresence>)>,
     mut commands: Commands,
 ) -> Result<(), ExplError> {
-    let map_entity = map_query.single()?;
-    let members = party_query.get(trigger.target())?;
+    let (members, on_map) = party_query.get(trigger.target())?;
     if members.is_empty() {
-        commands
-            .entity(map_entity)
-            .despawn_presence(trigger.target());
+        commands.entity(on_map.0).despawn_presence(trigger.target());
     }
     Ok(())
 }
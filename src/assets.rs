@@ -1,13 +1,28 @@
 use crate::{
-    actor::Actor,
+    actor::{Actor, RawActor},
     creature::Creature,
+    locale::LocaleTable,
     map_generator::MapTemplate,
-    structure::Structure,
-    terrain::{Decoration, Terrain},
+    structure::{RawStructure, Structure},
+    terrain::{Decoration, RawDecoration, Terrain},
+};
+use bevy::{
+    asset::{io::file::FileAssetReader, AssetPath},
+    prelude::*,
 };
-use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
-use expl_codex::Codex;
+use expl_codex::{
+    Codex, CodexFormat, CodexLoader, CodexPlugin, CodexSource, FromWithLoadContext, Schema,
+};
+use serde::de::DeserializeOwned;
+use std::{fmt::Debug, fs, path::Path};
+
+/// Directory below the asset root that mods are installed into.
+///
+/// Each mod is a directory that may contain codex overlays in a `codex` subdirectory, e.g
+/// `mods/my-mod/codex/stronger-slimes.creature.toml`. Overlays can be written in any of the
+/// [`CodexFormat`]s.
+pub const MODS_PATH: &str = "mods";
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States, Default)]
 pub enum AssetState {
@@ -22,6 +37,8 @@ pub struct MainAssets {
     pub swords_emblem_icon: Handle<Image>,
     #[asset(path = "maps/default.template.txt")]
     pub map_template: Handle<MapTemplate>,
+    #[asset(path = "locale/en.locale.toml")]
+    pub locale: Handle<LocaleTable>,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -50,3 +67,183 @@ impl Plugin for AssetsPlugin {
         );
     }
 }
+
+pub trait CodexAppExt {
+    /// Register a codex asset type and a loader that layers overlays from installed mods on top of
+    /// the base codex. Reloads of the codex are reported with [`expl_codex::CodexChanged`] events.
+    fn init_codex<RawEntry, Entry>(&mut self) -> &mut Self
+    where
+        Entry: CodexSource + Debug + PartialEq + TypePath + FromWithLoadContext<RawEntry>,
+        RawEntry: DeserializeOwned + Send + Sync + 'static;
+}
+
+impl CodexAppExt for App {
+    fn init_codex<RawEntry, Entry>(&mut self) -> &mut Self
+    where
+        Entry: CodexSource + Debug + PartialEq + TypePath + FromWithLoadContext<RawEntry>,
+        RawEntry: DeserializeOwned + Send + Sync + 'static,
+    {
+        let overlays = codex_overlays(Entry::EXTENSION);
+        for overlay in &overlays {
+            info!("Using codex overlay {}", overlay);
+        }
+        self.init_asset::<Codex<Entry>>()
+            .register_asset_loader(CodexLoader::<RawEntry, Entry>::with_layers(overlays))
+            .add_plugins(CodexPlugin::<Entry>::default())
+    }
+}
+
+/// Find the codex overlays with the given extension in the mods directory.
+///
+/// Mods are applied in the alphabetical order of their directory name and the files of each mod in
+/// the alphabetical order of their file name.
+fn codex_overlays(extension: &str) -> Vec<AssetPath<'static>> {
+    let asset_root = FileAssetReader::get_base_path().join(AssetPlugin::default().file_path);
+    let mut overlays = Vec::new();
+    for mod_name in sorted_entries(&asset_root.join(MODS_PATH)) {
+        let codex_path = Path::new(MODS_PATH).join(&mod_name).join("codex");
+        for (file_name, _) in codex_files(&asset_root.join(&codex_path), extension) {
+            overlays.push(AssetPath::from(codex_path.join(file_name)));
+        }
+    }
+    overlays
+}
+
+/// List the codex files in `directory` with the given extension in alphabetical order, along with
+/// the format each file is written in.
+pub fn codex_files(directory: &Path, extension: &str) -> Vec<(String, CodexFormat)> {
+    sorted_entries(directory)
+        .into_iter()
+        .filter_map(|file_name| {
+            let format = CodexFormat::ALL.into_iter().find(|format| {
+                file_name.ends_with(&format!(".{}.{}", extension, format.extension()))
+            })?;
+            Some((file_name, format))
+        })
+        .collect()
+}
+
+/// The extension and raw entry schema of each codex used by the game.
+pub fn codex_schemas() -> Vec<(&'static str, Schema)> {
+    vec![
+        (Terrain::EXTENSION, Schema::of::<Terrain>()),
+        (Decoration::EXTENSION, Schema::of::<RawDecoration>()),
+        (Structure::EXTENSION, Schema::of::<RawStructure>()),
+        (Creature::EXTENSION, Schema::of::<Creature>()),
+        (Actor::EXTENSION, Schema::of::<RawActor>()),
+    ]
+}
+
+fn sorted_entries(path: &Path) -> Vec<String> {
+    let Ok(read_dir) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<String> = read_dir
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    entries.sort();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::reflect::Typed;
+    use expl_codex::Validator;
+    use std::path::PathBuf;
+
+    fn default_codex() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/codex")
+    }
+
+    /// Paths of the fields set in `value` that the schema requires.
+    fn required_fields(schema: &Schema, value: &toml::Value) -> Vec<Vec<&'static str>> {
+        let (Schema::Object(fields), toml::Value::Table(table)) = (schema, value) else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        for field in fields {
+            let Some(field_value) = table.get(field.name) else {
+                continue;
+            };
+            if !field.optional {
+                paths.push(vec![field.name]);
+            }
+            for mut path in required_fields(&field.schema, field_value) {
+                path.insert(0, field.name);
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    fn remove_field(value: &mut toml::Value, path: &[&str]) {
+        let toml::Value::Table(table) = value else {
+            return;
+        };
+        match path {
+            [name] => {
+                table.remove(*name);
+            }
+            [name, rest @ ..] => {
+                if let Some(field_value) = table.get_mut(*name) {
+                    remove_field(field_value, rest);
+                }
+            }
+            [] => {}
+        }
+    }
+
+    /// Fields that serde fills in with a default must be marked with `@Optional` as well, so check
+    /// that no required field of the default entries can be left out.
+    fn assert_defaults_are_optional<T: DeserializeOwned + Typed>(extension: &str) {
+        let schema = Schema::of::<T>();
+        let directory = default_codex();
+        for (file_name, _) in codex_files(&directory, extension) {
+            let source = fs::read_to_string(directory.join(&file_name)).unwrap();
+            let entries: toml::Table = toml::from_str(&source).unwrap();
+            for (tag, entry) in entries {
+                entry.clone().try_into::<T>().unwrap();
+                for path in required_fields(&schema, &entry) {
+                    let mut stripped = entry.clone();
+                    remove_field(&mut stripped, &path);
+                    assert!(
+                        stripped.try_into::<T>().is_err(),
+                        "`{}.{}` in {} can be left out but is not marked `@Optional`",
+                        tag,
+                        path.join("."),
+                        file_name,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn default_codex_is_valid() {
+        let directory = default_codex();
+        let assets = directory.parent().unwrap();
+        for (extension, schema) in codex_schemas() {
+            let mut validator = Validator::new(&schema, |path| assets.join(path).is_file());
+            for (file_name, format) in codex_files(&directory, extension) {
+                let source = fs::read_to_string(directory.join(&file_name)).unwrap();
+                let diagnostics: Vec<String> = validator
+                    .validate(format, &source)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                assert_eq!(diagnostics, Vec::<String>::new(), "{}", file_name);
+            }
+        }
+    }
+
+    #[test]
+    fn serde_defaults_are_optional() {
+        assert_defaults_are_optional::<Terrain>(Terrain::EXTENSION);
+        assert_defaults_are_optional::<RawDecoration>(Decoration::EXTENSION);
+        assert_defaults_are_optional::<RawStructure>(Structure::EXTENSION);
+        assert_defaults_are_optional::<Creature>(Creature::EXTENSION);
+        assert_defaults_are_optional::<RawActor>(Actor::EXTENSION);
+    }
+}
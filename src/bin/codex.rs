@@ -0,0 +1,90 @@
+use clap::{Parser, Subcommand};
+use expl_codex::{codex_json_schema, Validator};
+use explore_game::assets::{codex_files, codex_schemas};
+use itertools::Itertools;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Inspect and check codex files without starting the game.
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the JSON Schema of the files of a codex, e.g `creature`.
+    Schema { codex: String },
+    /// Validate the codex files in one or more directories.
+    ///
+    /// The directories are layered in the order they are given, so a mod can be checked together
+    /// with the base codex, e.g `validate assets/codex assets/mods/my-mod/codex`.
+    Validate {
+        #[arg(required = true)]
+        directories: Vec<PathBuf>,
+        /// Directory that asset references are resolved against.
+        #[arg(long, default_value = "assets")]
+        assets: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let schemas = codex_schemas();
+
+    match cli.command {
+        Command::Schema { codex } => {
+            let Some((extension, schema)) =
+                schemas.iter().find(|(extension, _)| *extension == codex)
+            else {
+                eprintln!(
+                    "unknown codex `{}`, expected one of {}",
+                    codex,
+                    schemas.iter().map(|(extension, _)| extension).join(", ")
+                );
+                return ExitCode::FAILURE;
+            };
+            println!("{:#}", codex_json_schema(extension, schema));
+            ExitCode::SUCCESS
+        }
+        Command::Validate {
+            directories,
+            assets,
+        } => {
+            let mut files = 0;
+            let mut problems = 0;
+            for (extension, schema) in &schemas {
+                let mut validator = Validator::new(schema, |path| assets.join(path).is_file());
+                for directory in &directories {
+                    for (file_name, format) in codex_files(directory, extension) {
+                        let path = directory.join(file_name);
+                        files += 1;
+                        let source = match fs::read_to_string(&path) {
+                            Ok(source) => source,
+                            Err(err) => {
+                                eprintln!("{}: {}", path.display(), err);
+                                problems += 1;
+                                continue;
+                            }
+                        };
+                        for diagnostic in validator.validate(format, &source) {
+                            match diagnostic.location {
+                                Some(_) => eprintln!("{}:{}", path.display(), diagnostic),
+                                None => eprintln!("{}: {}", path.display(), diagnostic),
+                            }
+                            problems += 1;
+                        }
+                    }
+                }
+            }
+
+            if problems > 0 {
+                eprintln!("{} problem(s) found in {} codex file(s)", problems, files);
+                ExitCode::FAILURE
+            } else {
+                println!("{} codex file(s) ok", files);
+                ExitCode::SUCCESS
+            }
+        }
+    }
+}
@@ -25,6 +25,7 @@ impl CombatBundle {
                 position,
                 initiative: 0,
                 initiative_order,
+                withdrawn: false,
             },
             sprite: Sprite {
                 image: main_assets.swords_emblem_icon.clone(),
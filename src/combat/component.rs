@@ -7,4 +7,59 @@ pub struct Combat {
     pub(super) position: HexCoord,
     pub(super) initiative_order: SmallVec<[Entity; 8]>,
     pub(super) initiative: usize,
+    /// Whether any characters have withdrawn from the combat.
+    pub(super) withdrawn: bool,
+}
+
+impl Combat {
+    pub fn position(&self) -> HexCoord {
+        self.position
+    }
+
+    pub fn combatants(&self) -> &[Entity] {
+        &self.initiative_order
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.initiative_order.contains(&entity)
+    }
+
+    /// Take the characters of a retreating party out of the combat.
+    pub fn withdraw(&mut self, characters: &[Entity]) {
+        let next = self.initiative_order.get(self.initiative).copied();
+        self.withdrawn |= characters
+            .iter()
+            .any(|entity| self.initiative_order.contains(entity));
+        self.initiative_order
+            .retain(|entity| !characters.contains(entity));
+        self.initiative = next
+            .and_then(|next| self.initiative_order.iter().position(|&e| e == next))
+            .unwrap_or(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+
+    #[test]
+    fn withdraw() {
+        let [friend, other_friend, foe] = [
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        ];
+        let mut combat = Combat {
+            position: HexCoord::ZERO,
+            initiative_order: smallvec![friend, other_friend, foe],
+            initiative: 2,
+            withdrawn: false,
+        };
+        combat.withdraw(&[friend, other_friend]);
+        assert!(combat.withdrawn);
+        assert!(!combat.contains(friend));
+        assert_eq!(combat.combatants(), &[foe]);
+        assert_eq!(combat.initiative, 0);
+    }
 }
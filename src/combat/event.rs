@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use expl_hexgrid::HexCoord;
 
 #[derive(Event)]
 pub enum CombatEvent {
@@ -6,3 +7,11 @@ pub enum CombatEvent {
     FriendDamage(Entity, u16),
     EnemyDamage(Entity, u16),
 }
+
+/// Start combat between the characters of `party` and the enemies at `position`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct InitiateCombat {
+    pub party: Entity,
+    pub map: Entity,
+    pub position: HexCoord,
+}
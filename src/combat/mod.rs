@@ -8,3 +8,4 @@ pub use bundle::*;
 pub use component::*;
 pub use event::*;
 pub use plugin::CombatPlugin;
+pub use system::combat_round;
@@ -9,6 +9,7 @@ pub struct CombatPlugin;
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<CombatEvent>()
+            .add_observer(initiate_attack)
             .add_systems(ActionUpdate, initiate_combat.run_if(on_event::<MapEvent>))
             .add_systems(
                 Update,
@@ -4,9 +4,11 @@ use crate::{
     assets::MainAssets,
     creature::{Attack, Corpse, Health},
     floating_text::{FloatingTextAlignment, FloatingTextPrototype, FloatingTextSource},
+    map_generator::GameRng,
 };
 use bevy::{color::palettes::css, prelude::*};
-use expl_map::{MapCommandsExt, MapEvent, PresenceLayer};
+use expl_hexgrid::HexCoord;
+use expl_map::{MapCommandsExt, MapEvent, OnMap, Presences};
 use rand::Rng;
 
 pub fn combat_log(mut combat_events: EventReader<CombatEvent>, combat_query: Query<&Combat>) {
@@ -40,49 +42,90 @@ pub fn combat_log(mut combat_events: EventReader<CombatEvent>, combat_query: Que
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+fn spawn_combat(
+    commands: &mut Commands,
+    combat_events: &mut EventWriter<CombatEvent>,
+    main_assets: &Res<MainAssets>,
+    position: HexCoord,
+    friends: &[Entity],
+    foes: &[Entity],
+) {
+    if friends.is_empty() || foes.is_empty() {
+        return;
+    }
+    let initiative_order = friends.iter().chain(foes.iter()).cloned().collect();
+    let entity = commands
+        .spawn(CombatBundle::new(main_assets, position, initiative_order))
+        .id();
+    combat_events.write(CombatEvent::Initiate(entity));
+}
+
 pub fn initiate_combat(
     mut commands: Commands,
     mut map_events: EventReader<MapEvent>,
     mut combat_events: EventWriter<CombatEvent>,
     main_assets: Res<MainAssets>,
-    map_query: Query<&PresenceLayer>,
-    friend_query: Query<&Members>,
+    friend_presences: Presences<&Members>,
     character_query: Query<Entity, With<Character>>,
-    foe_query: Query<Entity, With<Enemy>>,
+    foe_presences: Presences<Entity, With<Enemy>>,
 ) {
-    let Ok(presence_layer) = map_query.single() else {
-        return;
-    };
     for event in map_events.read() {
-        let MapEvent::PresenceMoved { position, .. } = event else {
+        let MapEvent::PresenceMoved { map, position, .. } = event else {
             continue;
         };
-        let friends: Vec<_> = friend_query
-            .iter_many(presence_layer.presence(*position))
+        let friends: Vec<_> = friend_presences
+            .at(*map, *position)
             .flat_map(|members| character_query.iter_many(members.iter()))
             .collect();
-        let foes: Vec<_> = foe_query
-            .iter_many(presence_layer.presence(*position))
-            .collect();
-        let initiative_order = friends.iter().chain(foes.iter()).cloned().collect();
-        if !friends.is_empty() && !foes.is_empty() {
-            let entity = commands
-                .spawn(CombatBundle::new(&main_assets, *position, initiative_order))
-                .id();
-            combat_events.write(CombatEvent::Initiate(entity));
-        }
+        let foes: Vec<_> = foe_presences.at(*map, *position).collect();
+        spawn_combat(
+            &mut commands,
+            &mut combat_events,
+            &main_assets,
+            *position,
+            &friends,
+            &foes,
+        );
     }
 }
 
+pub fn initiate_attack(
+    trigger: Trigger<InitiateCombat>,
+    mut commands: Commands,
+    mut combat_events: EventWriter<CombatEvent>,
+    main_assets: Res<MainAssets>,
+    members_query: Query<&Members>,
+    character_query: Query<Entity, With<Character>>,
+    foe_presences: Presences<Entity, With<Enemy>>,
+) {
+    let InitiateCombat {
+        party,
+        map,
+        position,
+    } = *trigger.event();
+    let friends: Vec<_> = members_query
+        .get(party)
+        .map(|members| character_query.iter_many(members.iter()).collect())
+        .unwrap_or_default();
+    let foes: Vec<_> = foe_presences.at(map, position).collect();
+    spawn_combat(
+        &mut commands,
+        &mut combat_events,
+        &main_assets,
+        position,
+        &friends,
+        &foes,
+    );
+}
+
 #[allow(clippy::type_complexity)]
 pub fn combat_round(
     mut combat_query: Query<(Entity, &mut Combat)>,
     mut combat_events: EventWriter<CombatEvent>,
     attacker_query: Query<(&Attack, Option<&Enemy>)>,
     mut target_query: Query<(&mut Health, Option<&Enemy>)>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::thread_rng();
     for (entity, mut combat) in &mut combat_query {
         info!("Combat at {}", combat.position);
 
@@ -113,20 +156,29 @@ pub fn combat_round(
 #[allow(clippy::type_complexity)]
 pub fn make_corpses(
     mut commands: Commands,
-    map_query: Query<Entity, With<PresenceLayer>>,
-    health_query: Query<(Entity, &Health, Option<&Group>, Option<&Enemy>), Without<Corpse>>,
+    health_query: Query<
+        (
+            Entity,
+            &Health,
+            Option<&Group>,
+            Option<&Enemy>,
+            Option<&OnMap>,
+        ),
+        Without<Corpse>,
+    >,
 ) {
-    let Ok(map_entity) = map_query.single() else {
-        return;
-    };
-    for (entity, health, maybe_member, maybe_enemy) in &health_query {
+    for (entity, health, maybe_member, maybe_enemy, maybe_on_map) in &health_query {
         if health.current == 0 {
             info!("{:?} is dead", entity);
             if let Some(group) = maybe_member.map(|member| member.get()) {
                 commands.entity(group).remove_members(&[entity]);
             }
             if maybe_enemy.is_some() {
-                commands.entity(map_entity).despawn_presence(entity);
+                if let Some(on_map) = maybe_on_map {
+                    commands.entity(on_map.0).despawn_presence(entity);
+                } else {
+                    commands.entity(entity).despawn();
+                }
             } else {
                 commands.entity(entity).insert(Corpse);
             }
@@ -140,6 +192,7 @@ pub fn finish_combat(
     combat_query: Query<(Entity, &Combat)>,
     friend_query: Query<Entity, (With<Character>, Without<Corpse>, Without<Enemy>)>,
     foe_query: Query<Entity, (With<Enemy>, Without<Character>)>,
+    fallen_query: Query<(), (With<Character>, With<Corpse>)>,
 ) {
     for (entity, combat) in &combat_query {
         let no_friends = friend_query
@@ -150,8 +203,16 @@ pub fn finish_combat(
             .iter_many(&combat.initiative_order)
             .next()
             .is_none();
+        // Only a retreat if every friend that left the fight did so by withdrawing.
+        let retreated = combat.withdrawn
+            && fallen_query
+                .iter_many(&combat.initiative_order)
+                .next()
+                .is_none();
         if no_friends && no_foes {
             info!("Combat at {} leaves no one alive", combat.position);
+        } else if no_friends && retreated {
+            info!("Retreated from combat at {}", combat.position);
         } else if no_friends {
             info!("Suffers defeat in combat at {}", combat.position);
         } else if no_foes {
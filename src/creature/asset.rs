@@ -1,16 +1,21 @@
 use super::component::Attack;
+use crate::locale::impl_localized_entry;
 use bevy::prelude::*;
 use expl_codex::CodexSource;
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Default, Deserialize, TypePath)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Reflect)]
 pub struct Creature {
     pub attack: Attack,
     pub health: u16,
     pub action_points: u16,
     pub view_radius: u16,
+    pub name_key: Option<String>,
+    pub description_key: Option<String>,
 }
 
 impl CodexSource for Creature {
-    const EXTENSION: &'static str = "creature.toml";
+    const EXTENSION: &'static str = "creature";
 }
+
+impl_localized_entry!(Creature, creature_codex);
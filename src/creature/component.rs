@@ -33,7 +33,7 @@ impl Health {
     }
 }
 
-#[derive(Clone, Debug, Default, Component, Reflect, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Component, Reflect, Deserialize)]
 #[reflect(Component)]
 pub struct Attack {
     pub low: u16,
@@ -2,6 +2,7 @@ mod asset;
 mod bundle;
 mod component;
 mod plugin;
+mod system;
 mod system_param;
 
 pub use asset::*;
@@ -1,17 +1,27 @@
-use super::{asset::*, component::*};
+use super::{asset::*, component::*, system::*};
+use crate::{
+    assets::{AssetState, CodexAppExt},
+    error,
+};
 use bevy::prelude::*;
-use expl_codex::{Codex, CodexLoader, Id};
+use expl_codex::{CodexChanged, Id};
 
 pub struct CreaturePlugin;
 
 impl Plugin for CreaturePlugin {
     fn build(&self, app: &mut App) {
-        app.init_asset::<Codex<Creature>>()
-            .init_asset_loader::<CodexLoader<Creature>>()
+        app.init_codex::<Creature, Creature>()
             .register_type::<Attack>()
             .register_type::<Corpse>()
             .register_type::<CreatureId>()
             .register_type::<Health>()
-            .register_type::<Id<Creature>>();
+            .register_type::<Id<Creature>>()
+            .add_systems(
+                Update,
+                update_creature_from_codex
+                    .map(error::warn)
+                    .run_if(in_state(AssetState::Loaded))
+                    .run_if(on_event::<CodexChanged<Creature>>),
+            );
     }
 }
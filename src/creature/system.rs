@@ -0,0 +1,29 @@
+use super::{asset::*, component::*, system_param::CreatureCodex};
+use crate::{action::ActionPoints, ExplError};
+use bevy::prelude::*;
+use expl_codex::CodexChanged;
+
+/// Apply tuned codex values to creatures that are already spawned.
+pub fn update_creature_from_codex(
+    mut codex_changed_events: EventReader<CodexChanged<Creature>>,
+    creature_codex: CreatureCodex,
+    mut creature_query: Query<(&CreatureId, &mut Health, &mut Attack, &mut ActionPoints)>,
+) -> Result<(), ExplError> {
+    let creature_codex = creature_codex.get()?;
+    for event in codex_changed_events.read() {
+        for (creature_id, mut health, mut attack, mut action_points) in &mut creature_query {
+            if !event.modified.contains(creature_id) {
+                continue;
+            }
+            let creature_data = &creature_codex[creature_id];
+            // Keep the damage taken while adjusting to the new maximum
+            let damage = health.max.saturating_sub(health.current);
+            health.max = creature_data.health;
+            health.current = health.max.saturating_sub(damage);
+            *attack = creature_data.attack.clone();
+            action_points.reset = creature_data.action_points;
+            action_points.current = action_points.current.min(action_points.reset);
+        }
+    }
+    Ok(())
+}
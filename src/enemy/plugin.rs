@@ -1,6 +1,6 @@
 pub struct EnemyPlugin;
 use super::system::*;
-use crate::{error, scene::SceneState, turn::TurnState};
+use crate::{error, journal::Replay, scene::SceneState, turn::TurnState};
 use bevy::prelude::*;
 
 impl Plugin for EnemyPlugin {
@@ -9,7 +9,8 @@ impl Plugin for EnemyPlugin {
             OnEnter(TurnState::System),
             move_enemy
                 .map(error::warn)
-                .run_if(in_state(SceneState::Active)),
+                .run_if(in_state(SceneState::Active))
+                .run_if(not(resource_exists::<Replay>)),
         );
     }
 }
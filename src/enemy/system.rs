@@ -6,21 +6,24 @@ use crate::{
     ExplError,
 };
 use bevy::prelude::*;
-use expl_map::{HexCoord, MapPresence, PresenceLayer, ViewRadius, ZoneLayer};
+use expl_map::{Faction, HexCoord, MapPresence, OnMap, PresenceLayer, ViewRadius, ZoneLayer};
 use rand::{seq::SliceRandom, thread_rng};
 
 pub fn move_enemy(
     mut queue: ResMut<GameActionQueue>,
     map_query: Query<(&ZoneLayer, &PresenceLayer)>,
-    enemy_query: Query<(Entity, &MapPresence, &ViewRadius), With<Enemy>>,
+    enemy_query: Query<(Entity, &MapPresence, &ViewRadius, &OnMap), With<Enemy>>,
     target: Target,
     path_finder: PathFinder,
 ) -> Result<(), ExplError> {
-    let (zone_layer, presence_layer) = map_query.single()?;
     let pf = path_finder.get()?;
+    // Enemy moves are journaled and not rerun on replay, so wandering stays off `GameRng`.
     let mut rng = thread_rng();
-    for (entity, presence, view_radius) in &enemy_query {
-        if let Some(target) = target.closest_in_view(presence.position, view_radius) {
+    for (entity, presence, view_radius, on_map) in &enemy_query {
+        let (zone_layer, presence_layer) = map_query.get(on_map.0)?;
+        if let Some(target) =
+            target.closest_visible(on_map.0, presence.position, view_radius, Faction::ENEMY)
+        {
             let Some(path) = pf.find_path(presence.position, target.position) else {
                 continue;
             };
@@ -1,21 +1,25 @@
 use crate::actor::Party;
 use bevy::{ecs::system::SystemParam, prelude::*};
-use expl_map::{HexCoord, MapPresence, ViewRadius};
+use expl_map::{Faction, FactionVisibility, HexCoord, MapPresence, Presences, ViewRadius};
 
 #[derive(SystemParam)]
 pub struct Target<'w, 's> {
-    presence_query: Query<'w, 's, &'static MapPresence, With<Party>>,
+    parties: Presences<'w, 's, &'static MapPresence, With<Party>>,
+    visibility: FactionVisibility<'w, 's>,
 }
 
 impl Target<'_, '_> {
-    pub fn closest_in_view(
+    /// Closest party within `view_radius` that is visible to any presence of the faction.
+    pub fn closest_visible(
         &self,
+        map: Entity,
         position: HexCoord,
         view_radius: &ViewRadius,
+        faction: Faction,
     ) -> Option<&MapPresence> {
-        self.presence_query
-            .iter()
-            .filter(|&other| position.distance(other.position) <= view_radius.0)
-            .min_by_key(|other| position.distance(other.position))
+        self.parties
+            .within(map, position, view_radius.0)
+            .find(|&(other, _)| self.visibility.is_visible(map, other, faction))
+            .map(|(_, presence)| presence)
     }
 }
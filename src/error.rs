@@ -6,6 +6,12 @@ pub enum ExplError {
     #[error(transparent)]
     IOError(#[from] std::io::Error),
     #[error(transparent)]
+    Utf8Error(#[from] std::str::Utf8Error),
+    #[error(transparent)]
+    TomlError(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSerError(#[from] toml::ser::Error),
+    #[error(transparent)]
     WFCError(#[from] expl_wfc::WFCError),
     #[error("query does not match `{0}`")]
     QueryDoesNotMatch(Entity),
@@ -47,8 +53,14 @@ pub enum ExplError {
     MissingTemplate,
     #[error("invalid action target")]
     InvalidTarget,
+    #[error(transparent)]
+    ActionBlocked(#[from] crate::action::ActionBlocked),
     #[error("missing material")]
     MissingMaterial,
+    #[error("journal has no seed")]
+    MissingSeed,
+    #[error("saved map has no zone at {0}")]
+    MissingZone(expl_hexgrid::HexCoord),
 }
 
 impl<I, O> From<bevy::ecs::system::RegisteredSystemError<I, O>> for ExplError
@@ -1,20 +1,23 @@
-use super::{Deselect, NextSelectionQuery, Select, Selection};
+use super::{ActionAvailability, Deselect, MapHover, NextSelectionQuery, Select, Selection};
 use crate::{
-    action::{GameAction, GameActionQueue},
-    actor::{Character, Members, Party},
+    action::{ActionBlocked, ActionCheckResult, GameAction, GameActionQueue, GameActions},
+    actor::{Character, Enemy, Members, Party},
     camera::{CameraControl, CameraTarget},
     interface::InterfaceState,
-    path::PathGuided,
+    path::{AutoExplore, PathFinder, PathGuided},
     structure::Camp,
     ExplError,
 };
-use bevy::prelude::*;
-use expl_map::{MapPresence, PresenceLayer};
+use bevy::{ecs::system::RegisteredSystemError, prelude::*};
+use expl_map::{HexCoord, MapPresence, OnMap, PresenceLayer, Presences, ZoneLayer};
 pub use leafwing_input_manager::prelude::ActionState;
 use leafwing_input_manager::prelude::*;
+use smallvec::SmallVec;
 
 #[derive(Reflect, PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub enum Action {
+    Attack,
+    AutoExplore,
     BreakCamp,
     Camp,
     Cancel,
@@ -22,6 +25,8 @@ pub enum Action {
     CreateParty,
     Deselect,
     EnterPortal,
+    Forage,
+    Hunt,
     MergeParty,
     MultiSelect,
     NextTurn,
@@ -33,11 +38,14 @@ pub enum Action {
     PanCameraRight,
     PanCameraUp,
     ResumeMove,
+    Retreat,
     Save,
+    Scout,
     SelectNext,
     SplitParty,
     ToggleInspector,
     ToggleMainMenu,
+    Undo,
     ZoomCamera,
 }
 
@@ -84,15 +92,6 @@ pub fn handle_deselect(mut commands: Commands, selection_query: Query<(Entity, &
     }
 }
 
-pub fn handle_enter_portal(
-    party_query: Query<(Entity, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
-    for (entity, _) in party_query.iter().filter(|(_, s)| s.is_selected) {
-        game_action_queue.add(GameAction::new_enter_portal(entity));
-    }
-}
-
 pub fn handle_select_next(
     mut commands: Commands,
     next_selection_query: NextSelectionQuery,
@@ -116,41 +115,60 @@ pub fn handle_select_next(
     Ok(())
 }
 
-pub fn handle_camp(
-    party_query: Query<(Entity, &MapPresence, &Selection), With<Party>>,
-    map_query: Query<&PresenceLayer>,
-    camp_query: Query<Entity, With<Camp>>,
+/// The game actions that the selection would perform for a toolbar action.
+pub type PlannedActions = SmallVec<[GameAction; 4]>;
+
+pub fn queue_planned_actions(
+    In(actions): In<PlannedActions>,
     mut game_action_queue: ResMut<GameActionQueue>,
 ) {
-    for (entity, presence, _) in party_query.iter().filter(|(_, _, s)| s.is_selected) {
-        let Ok(presence_layer) = map_query.single() else {
-            continue;
-        };
-        if let Some(camp_entity) = camp_query
-            .iter_many(presence_layer.presence(presence.position))
-            .next()
-        {
-            game_action_queue.add(GameAction::new_enter_camp(entity, camp_entity));
-        } else {
-            game_action_queue.add(GameAction::new_make_camp(entity));
-        }
+    for action in actions {
+        game_action_queue.add(action);
     }
 }
 
-pub fn handle_break_camp(
-    party_query: Query<(Entity, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
-    for (entity, _) in party_query.iter().filter(|(_, s)| s.is_selected) {
-        game_action_queue.add(GameAction::new_break_camp(entity));
-    }
+pub fn plan_enter_portal(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(entity, _)| GameAction::new_enter_portal(entity))
+        .collect()
+}
+
+pub fn plan_camp(
+    party_query: Query<(Entity, &MapPresence, &OnMap, &Selection), With<Party>>,
+    map_query: Query<&PresenceLayer>,
+    camp_query: Query<Entity, With<Camp>>,
+) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, _, _, s)| s.is_selected)
+        .map(|(entity, presence, on_map, _)| {
+            match map_query.get(on_map.0).ok().and_then(|presence_layer| {
+                camp_query
+                    .iter_many(presence_layer.presence(presence.position))
+                    .next()
+            }) {
+                Some(camp_entity) => GameAction::new_enter_camp(entity, camp_entity),
+                None => GameAction::new_make_camp(entity),
+            }
+        })
+        .collect()
+}
+
+pub fn plan_break_camp(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(entity, _)| GameAction::new_break_camp(entity))
+        .collect()
 }
 
-pub fn handle_create_party(
+pub fn plan_create_party(
     camp_query: Query<(Entity, &Members, &Selection), With<Camp>>,
     character_query: Query<(Entity, &Selection), With<Character>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
+) -> PlannedActions {
+    let mut actions = PlannedActions::new();
     for (entity, members, _) in camp_query.iter().filter(|(_, _, s)| s.is_selected) {
         let selected: Vec<_> = character_query
             .iter_many(members.iter())
@@ -158,16 +176,17 @@ pub fn handle_create_party(
             .map(|(e, _)| e)
             .collect();
         if !selected.is_empty() {
-            game_action_queue.add(GameAction::new_create_party_from_camp(entity, selected));
+            actions.push(GameAction::new_create_party_from_camp(entity, selected));
         }
     }
+    actions
 }
 
-pub fn handle_split_party(
+pub fn plan_split_party(
     party_query: Query<(Entity, &Members, &Selection), With<Party>>,
     character_query: Query<(Entity, &Selection), With<Character>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
+) -> PlannedActions {
+    let mut actions = PlannedActions::new();
     for (entity, members, _) in party_query.iter().filter(|(_, _, s)| s.is_selected) {
         let selected: Vec<Entity> = character_query
             .iter_many(members.iter())
@@ -175,49 +194,248 @@ pub fn handle_split_party(
             .map(|(e, _)| e)
             .collect();
         if !selected.is_empty() {
-            game_action_queue.add(GameAction::new_split_party(entity, selected));
+            actions.push(GameAction::new_split_party(entity, selected));
         }
     }
+    actions
 }
 
-pub fn handle_merge_party(
-    party_query: Query<(Entity, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
+pub fn plan_merge_party(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
     let mut selected_parties = party_query
         .iter()
         .filter(|(_, s)| s.is_selected)
         .map(|(e, _)| e);
-    if let Some(source) = selected_parties.next() {
-        game_action_queue.add(GameAction::new_merge_party(source, selected_parties));
-    }
+    selected_parties
+        .next()
+        .map(|source| GameAction::new_merge_party(source, selected_parties))
+        .into_iter()
+        .collect()
 }
 
-pub fn handle_collect_crystals(
+pub fn plan_collect_crystals(
     party_query: Query<(Entity, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
-    for (party, _) in party_query.iter().filter(|(_, s)| s.is_selected) {
-        game_action_queue.add(GameAction::new_collect_crystals(party));
-    }
+) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(party, _)| GameAction::new_collect_crystals(party))
+        .collect()
 }
 
-pub fn handle_open_portal(
-    party_query: Query<(Entity, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
-) {
-    for (party, _) in party_query.iter().filter(|(_, s)| s.is_selected) {
-        game_action_queue.add(GameAction::new_open_portal(party));
-    }
+pub fn plan_open_portal(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(party, _)| GameAction::new_open_portal(party))
+        .collect()
+}
+
+pub fn plan_forage(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(party, _)| GameAction::new_forage(party))
+        .collect()
+}
+
+pub fn plan_hunt(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(party, _)| GameAction::new_hunt(party))
+        .collect()
+}
+
+/// An action from each selected party to one of the zones next to it.
+///
+/// The hovered zone is preferred if it is `suitable`, then any suitable zone. Otherwise the hovered
+/// or first neighbour is used so that the action check can tell why it is not possible.
+fn plan_adjacent(
+    party_query: &Query<(Entity, &MapPresence, &OnMap, &Selection), With<Party>>,
+    zone_layer_query: &Query<&ZoneLayer>,
+    map_hover: &MapHover,
+    suitable: impl Fn(Entity, HexCoord, Entity) -> bool,
+    new_action: fn(Entity, Entity) -> GameAction,
+) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, _, _, s)| s.is_selected)
+        .filter_map(|(party, presence, &OnMap(map), _)| {
+            let zone_layer = zone_layer_query.get(map).ok()?;
+            let neighbours: SmallVec<[(HexCoord, Entity); 6]> = presence
+                .position
+                .neighbours()
+                .filter_map(|position| Some((position, *zone_layer.get(position)?)))
+                .collect();
+            let hovered = neighbours
+                .iter()
+                .find(|&&(_, zone)| map_hover.zone == Some(zone));
+            hovered
+                .filter(|&&(position, zone)| suitable(map, position, zone))
+                .or_else(|| {
+                    neighbours
+                        .iter()
+                        .find(|&&(position, zone)| suitable(map, position, zone))
+                })
+                .or(hovered)
+                .or(neighbours.first())
+                .map(|&(_, zone)| new_action(party, zone))
+        })
+        .collect()
 }
 
-pub fn handle_resume_move(
+pub fn plan_attack(
+    party_query: Query<(Entity, &MapPresence, &OnMap, &Selection), With<Party>>,
+    zone_layer_query: Query<&ZoneLayer>,
+    map_hover: Res<MapHover>,
+    enemy_presences: Presences<(), With<Enemy>>,
+) -> PlannedActions {
+    plan_adjacent(
+        &party_query,
+        &zone_layer_query,
+        &map_hover,
+        |map, position, _| enemy_presences.count(map, position) > 0,
+        GameAction::new_attack,
+    )
+}
+
+pub fn plan_retreat(
+    party_query: Query<(Entity, &MapPresence, &OnMap, &Selection), With<Party>>,
+    zone_layer_query: Query<&ZoneLayer>,
+    map_hover: Res<MapHover>,
+    enemy_presences: Presences<(), With<Enemy>>,
+    path_finder: PathFinder,
+) -> PlannedActions {
+    let path_finder = path_finder.get().ok();
+    plan_adjacent(
+        &party_query,
+        &zone_layer_query,
+        &map_hover,
+        |map, position, zone| {
+            enemy_presences.count(map, position) == 0
+                && path_finder
+                    .as_ref()
+                    .is_none_or(|path_finder| path_finder.is_walkable(zone))
+        },
+        GameAction::new_retreat,
+    )
+}
+
+pub fn plan_scout(party_query: Query<(Entity, &Selection), With<Party>>) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, s)| s.is_selected)
+        .map(|(party, _)| GameAction::new_scout(party))
+        .collect()
+}
+
+pub fn plan_resume_move(
     party_query: Query<(Entity, &PathGuided, &Selection), With<Party>>,
-    mut game_action_queue: ResMut<GameActionQueue>,
+) -> PlannedActions {
+    party_query
+        .iter()
+        .filter(|(_, _, s)| s.is_selected)
+        .filter_map(|(entity, path_guided, _)| {
+            path_guided
+                .next()
+                .map(|&next| GameAction::new_move(entity, next))
+        })
+        .collect()
+}
+
+/// Give the selected parties the order to explore, or take it back if they all have it already.
+pub fn toggle_auto_explore(
+    mut commands: Commands,
+    party_query: Query<(Entity, Has<AutoExplore>, &Selection), With<Party>>,
 ) {
-    for (entity, path_guided, _) in party_query.iter().filter(|(_, _, s)| s.is_selected) {
-        if let Some(next) = path_guided.next() {
-            game_action_queue.add(GameAction::new_move(entity, *next));
+    let selected: SmallVec<[(Entity, bool); 8]> = party_query
+        .iter()
+        .filter(|(_, _, s)| s.is_selected)
+        .map(|(entity, auto_explore, _)| (entity, auto_explore))
+        .collect();
+    let explore = selected.iter().any(|&(_, auto_explore)| !auto_explore);
+    for (entity, auto_explore) in selected {
+        if explore && !auto_explore {
+            commands.entity(entity).insert(AutoExplore);
+        } else if !explore {
+            commands.entity(entity).remove::<AutoExplore>();
         }
     }
 }
+
+fn can_auto_explore(party_query: Query<&Selection, With<Party>>) -> ActionCheckResult {
+    if party_query.iter().any(|s| s.is_selected) {
+        Ok(())
+    } else {
+        Err(ActionBlocked::NothingSelected)
+    }
+}
+
+fn check_planned(
+    world: &mut World,
+    actions: Result<PlannedActions, RegisteredSystemError<(), PlannedActions>>,
+) -> ActionCheckResult {
+    let actions = actions.unwrap_or_default();
+    if actions.is_empty() {
+        return Err(ActionBlocked::NothingSelected);
+    }
+    actions
+        .iter()
+        .try_for_each(|action| GameActions::can_perform(world, action))
+}
+
+/// Dry run the toolbar actions for the current selection.
+pub fn update_action_availability(world: &mut World) {
+    let planned = [
+        (
+            Action::ResumeMove,
+            world.run_system_cached(plan_resume_move),
+        ),
+        (Action::Camp, world.run_system_cached(plan_camp)),
+        (Action::BreakCamp, world.run_system_cached(plan_break_camp)),
+        (
+            Action::CreateParty,
+            world.run_system_cached(plan_create_party),
+        ),
+        (
+            Action::SplitParty,
+            world.run_system_cached(plan_split_party),
+        ),
+        (
+            Action::MergeParty,
+            world.run_system_cached(plan_merge_party),
+        ),
+        (
+            Action::CollectCrystals,
+            world.run_system_cached(plan_collect_crystals),
+        ),
+        (
+            Action::OpenPortal,
+            world.run_system_cached(plan_open_portal),
+        ),
+        (
+            Action::EnterPortal,
+            world.run_system_cached(plan_enter_portal),
+        ),
+        (Action::Forage, world.run_system_cached(plan_forage)),
+        (Action::Hunt, world.run_system_cached(plan_hunt)),
+        (Action::Attack, world.run_system_cached(plan_attack)),
+        (Action::Retreat, world.run_system_cached(plan_retreat)),
+        (Action::Scout, world.run_system_cached(plan_scout)),
+    ];
+    let mut availability = ActionAvailability(
+        planned
+            .into_iter()
+            .map(|(action, actions)| (action, check_planned(world, actions)))
+            .collect(),
+    );
+    availability.0.insert(
+        Action::AutoExplore,
+        world
+            .run_system_cached(can_auto_explore)
+            .unwrap_or(Err(ActionBlocked::NothingSelected)),
+    );
+    if world.get_resource::<ActionAvailability>() != Some(&availability) {
+        world.insert_resource(availability);
+    }
+}
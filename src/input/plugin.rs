@@ -1,5 +1,5 @@
 use super::{action::*, component::*, resource::*, system::*};
-use crate::{error, turn};
+use crate::{action, assets::CodexAssets, error, turn};
 use bevy::{picking::PickSet, prelude::*};
 use leafwing_input_manager::{
     common_conditions::action_just_pressed, plugin::InputManagerSystem, prelude::*,
@@ -35,6 +35,7 @@ impl Plugin for InputPlugin {
             .add_observer(SelectedIndex::on_deselect)
             .add_observer(SelectedIndex::on_remove)
             .init_resource::<MapHover>()
+            .init_resource::<ActionAvailability>()
             .add_observer(MapHover::on_zone_over)
             .insert_resource(input_map())
             .add_observer(apply_zone_activated_event.map(error::warn))
@@ -45,22 +46,61 @@ impl Plugin for InputPlugin {
             .add_systems(
                 Update,
                 (
-                    handle_enter_portal.run_if(action_just_pressed(Action::EnterPortal)),
+                    plan_enter_portal
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::EnterPortal)),
                     handle_select_next
                         .map(error::warn)
                         .run_if(action_just_pressed(Action::SelectNext)),
-                    handle_resume_move.run_if(action_just_pressed(Action::ResumeMove)),
-                    handle_camp.run_if(action_just_pressed(Action::Camp)),
-                    handle_break_camp.run_if(action_just_pressed(Action::BreakCamp)),
-                    handle_create_party.run_if(action_just_pressed(Action::CreateParty)),
-                    handle_split_party.run_if(action_just_pressed(Action::SplitParty)),
-                    handle_merge_party.run_if(action_just_pressed(Action::MergeParty)),
-                    handle_collect_crystals.run_if(action_just_pressed(Action::CollectCrystals)),
-                    handle_open_portal.run_if(action_just_pressed(Action::OpenPortal)),
+                    plan_resume_move
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::ResumeMove)),
+                    toggle_auto_explore.run_if(action_just_pressed(Action::AutoExplore)),
+                    plan_camp
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::Camp)),
+                    plan_break_camp
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::BreakCamp)),
+                    plan_create_party
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::CreateParty)),
+                    plan_split_party
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::SplitParty)),
+                    plan_merge_party
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::MergeParty)),
+                    plan_collect_crystals
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::CollectCrystals)),
+                    plan_open_portal
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::OpenPortal)),
+                    plan_forage
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::Forage)),
+                    plan_hunt
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::Hunt)),
+                    plan_attack
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::Attack)),
+                    plan_retreat
+                        .pipe(queue_planned_actions)
+                        .run_if(resource_exists::<CodexAssets>)
+                        .run_if(action_just_pressed(Action::Retreat)),
+                    plan_scout
+                        .pipe(queue_planned_actions)
+                        .run_if(action_just_pressed(Action::Scout)),
+                    action::undo_last_action
+                        .run_if(action_just_pressed(Action::Undo))
+                        .run_if(action::can_undo),
                     turn::set_system_turn.run_if(action_just_pressed(Action::NextTurn)),
                 )
                     .after(InputManagerSystem::ManualControl),
             )
+            .add_systems(Update, update_action_availability)
             .add_systems(
                 PreUpdate,
                 (
@@ -85,7 +125,14 @@ fn input_map() -> InputMap<Action> {
         .with(Action::SelectNext, KeyCode::Space)
         .with(Action::ResumeMove, KeyCode::KeyM)
         .with(Action::Camp, KeyCode::KeyC)
+        .with(Action::AutoExplore, KeyCode::KeyE)
+        .with(Action::Forage, KeyCode::KeyF)
+        .with(Action::Hunt, KeyCode::KeyH)
+        .with(Action::Attack, KeyCode::KeyA)
+        .with(Action::Retreat, KeyCode::KeyR)
+        .with(Action::Scout, KeyCode::KeyS)
         .with(Action::NextTurn, KeyCode::Enter)
+        .with(Action::Undo, KeyCode::KeyZ)
         .with(Action::PanCamera, MouseButton::Right)
         .with(Action::ToggleInspector, KeyCode::F12)
         .with_axis(Action::ZoomCamera, MouseScrollAxis::Y)
@@ -1,5 +1,7 @@
-use super::{component::Selection, event::*};
+use super::{action::Action, component::Selection, event::*};
+use crate::action::{ActionBlocked, ActionCheckResult};
 use bevy::prelude::*;
+use std::collections::HashMap;
 
 #[derive(Resource, Default)]
 pub struct SelectedIndex(pub Vec<Entity>);
@@ -28,3 +30,14 @@ impl MapHover {
         map_hover.zone = Some(trigger.target());
     }
 }
+
+/// Whether the current selection can perform each toolbar action.
+#[derive(Resource, Default, PartialEq, Debug)]
+pub struct ActionAvailability(pub(super) HashMap<Action, ActionCheckResult>);
+
+impl ActionAvailability {
+    /// The reason `action` can not be performed, if it has been checked.
+    pub fn blocked(&self, action: Action) -> Option<&ActionBlocked> {
+        self.0.get(&action).and_then(|result| result.as_ref().err())
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     combat::Combat,
     error,
     material::ZoneMaterial,
-    path::{PathFinder, PathGuided},
+    path::{AutoExplore, PathFinder, PathGuided},
     terrain::TerrainId,
     ExplError,
 };
@@ -31,6 +31,7 @@ pub fn selection_added(trigger: Trigger<OnAdd, Selection>, mut commands: Command
 
 pub fn apply_zone_activated_event(
     trigger: Trigger<ZoneActivated>,
+    mut commands: Commands,
     mut presence_query: Query<(
         Entity,
         &MapPresence,
@@ -58,6 +59,7 @@ pub fn apply_zone_activated_event(
             continue;
         };
         pathguided.path(path.into_iter().map(|(_, e)| e));
+        commands.entity(entity).remove::<AutoExplore>();
         if action_points.current > 0 {
             if let Some(next) = pathguided.next() {
                 game_action_queue.add(GameAction::new_move(entity, *next));
@@ -9,6 +9,8 @@ pub struct InterfaceAssets {
     pub campfire_icon: Handle<Image>,
     #[asset(path = "icons/knapsack.png")]
     pub knapsack_icon: Handle<Image>,
+    #[asset(path = "icons/berries.png")]
+    pub berries_icon: Handle<Image>,
     #[asset(path = "icons/bottom-right-3d-arrow.png")]
     pub arrow_icon: Handle<Image>,
     #[asset(path = "icons/back-forth.png")]
@@ -31,6 +33,8 @@ pub struct InterfaceAssets {
     pub portal_icon: Handle<Image>,
     #[asset(path = "icons/magic-swirl.png")]
     pub magic_swirl_icon: Handle<Image>,
+    #[asset(path = "icons/swords-emblem.png")]
+    pub swords_icon: Handle<Image>,
     #[asset(path = "icons/brutal-helm.png")]
     pub brutal_helm_icon: Handle<Image>,
 }
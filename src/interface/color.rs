@@ -6,4 +6,5 @@ pub const NORMAL: Color = Color::srgb(0.20, 0.20, 0.20);
 pub const SELECTED: Color = Color::srgb(0.75, 0.50, 0.50);
 pub const PRESSED: Color = Color::srgb(0.25, 0.25, 0.25);
 pub const HOVERED: Color = Color::srgb(0.25, 0.25, 0.35);
+pub const DISABLED: Color = Color::srgb(0.12, 0.12, 0.12);
 pub const HIGHLIGHT: Color = Color::srgb(0.4, 0.9, 0.4);
@@ -0,0 +1,200 @@
+use super::super::{
+    color::NORMAL, prelude::*, resource::TransferTargets, styles::style_small_icon, widget::Button,
+    InterfaceAssets, DEFAULT_FONT,
+};
+use crate::{
+    action::{GameAction, GameActionQueue},
+    actor::Party,
+    inventory::{Inventory, Item},
+    structure::{Camp, SafeHaven},
+};
+use expl_codex::Id;
+
+fn style_inventory_panel(style: &mut StyleBuilder) {
+    style
+        .width(Val::Percent(100.0))
+        .margin(Val::Px(2.0))
+        .flex_direction(FlexDirection::Column)
+        .background_color(NORMAL);
+}
+
+fn style_partner_text(style: &mut StyleBuilder) {
+    style.font(DEFAULT_FONT).font_size(20.0).color(css::WHITE);
+}
+
+fn style_item_transfer(style: &mut StyleBuilder) {
+    style
+        .align_items(AlignItems::Center)
+        .justify_content(JustifyContent::SpaceBetween)
+        .font(DEFAULT_FONT)
+        .font_size(20.0)
+        .color(css::WHITE);
+}
+
+fn style_count_text(style: &mut StyleBuilder) {
+    style
+        .min_width(Val::Px(32.0))
+        .justify_content(JustifyContent::Center);
+}
+
+fn style_transfer_button(style: &mut StyleBuilder) {
+    style.width(Val::Px(28.0)).height(Val::Px(24.0));
+}
+
+/// Items that the target can hand over to the inventories next to it.
+#[derive(Clone, PartialEq)]
+pub struct InventoryPanel {
+    target: Entity,
+}
+
+impl InventoryPanel {
+    pub fn new(target: Entity) -> Self {
+        Self { target }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct TransferPartner {
+    holder: Entity,
+    partner: Entity,
+}
+
+#[derive(Clone, PartialEq)]
+struct ItemTransfer {
+    holder: Entity,
+    partner: Entity,
+    item_id: Id<Item>,
+    icon: Handle<Image>,
+    held: u32,
+    offered: u32,
+}
+
+#[derive(Clone, PartialEq)]
+struct TransferButton {
+    label: &'static str,
+    source: Entity,
+    target: Entity,
+    item_id: Id<Item>,
+    count: u32,
+}
+
+impl ViewTemplate for InventoryPanel {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let holder = self.target;
+        let partners = cx.use_resource::<TransferTargets>().get(holder).to_vec();
+        Cond::new(
+            !partners.is_empty(),
+            Element::<Node>::new()
+                .named("Inventory panel")
+                .style(style_inventory_panel)
+                .children(For::each(partners, move |&partner| TransferPartner {
+                    holder,
+                    partner,
+                })),
+            (),
+        )
+    }
+}
+
+impl ViewTemplate for TransferPartner {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let assets = cx.use_resource::<InterfaceAssets>();
+        let crystals_icon = assets.crystals_icon.clone();
+        let supply_icon = assets.knapsack_icon.clone();
+        let name = if let Some(party) = cx.use_component::<Party>(self.partner) {
+            party.name.clone()
+        } else if let Some(camp) = cx.use_component::<Camp>(self.partner) {
+            camp.name.clone()
+        } else if cx.use_component::<SafeHaven>(self.partner).is_some() {
+            "Safe haven".to_string()
+        } else {
+            String::new()
+        };
+        let held = cx
+            .use_component::<Inventory>(self.holder)
+            .cloned()
+            .unwrap_or_default();
+        let offered = cx
+            .use_component::<Inventory>(self.partner)
+            .cloned()
+            .unwrap_or_default();
+        let item_transfer = |item_id, icon| ItemTransfer {
+            holder: self.holder,
+            partner: self.partner,
+            item_id,
+            icon,
+            held: held.count_item(item_id),
+            offered: offered.count_item(item_id),
+        };
+
+        (
+            Element::<Node>::new()
+                .style(style_partner_text)
+                .children(name),
+            item_transfer(Inventory::CRYSTAL, crystals_icon),
+            item_transfer(Inventory::SUPPLY, supply_icon),
+        )
+    }
+}
+
+impl ViewTemplate for ItemTransfer {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let give = |label, count| TransferButton {
+            label,
+            source: self.holder,
+            target: self.partner,
+            item_id: self.item_id,
+            count,
+        };
+        let take = |label, count| TransferButton {
+            label,
+            source: self.partner,
+            target: self.holder,
+            item_id: self.item_id,
+            count,
+        };
+
+        Element::<Node>::new().style(style_item_transfer).children((
+            Element::<Node>::new()
+                .style(style_count_text)
+                .children(format!("{}", self.held)),
+            take("<<", self.offered),
+            take("<", self.offered.min(1)),
+            Element::<ImageNode>::new()
+                .style(style_small_icon)
+                .insert(ImageNode::from(self.icon.clone())),
+            give(">", self.held.min(1)),
+            give(">>", self.held),
+            Element::<Node>::new()
+                .style(style_count_text)
+                .children(format!("{}", self.offered)),
+        ))
+    }
+}
+
+impl ViewTemplate for TransferButton {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let (source, target, item_id, count) = (self.source, self.target, self.item_id, self.count);
+        Button::new()
+            .on_click(
+                cx.create_callback(move |mut game_action_queue: ResMut<GameActionQueue>| {
+                    game_action_queue.add(GameAction::new_transfer_items(
+                        source,
+                        target,
+                        [(item_id, count)],
+                    ));
+                }),
+            )
+            .style(style_transfer_button)
+            .disabled(count == 0)
+            .children(self.label)
+    }
+}
@@ -2,8 +2,10 @@
 /// parts of the engine state.
 mod camp;
 mod character;
+mod inventory;
 mod party;
 
 pub use camp::{CampDetails, CampList};
 pub use character::CharacterList;
+pub use inventory::InventoryPanel;
 pub use party::{PartyDetails, PartyList};
@@ -21,6 +21,7 @@ impl Plugin for InterfacePlugin {
         .init_state::<InterfaceState>()
         .init_resource::<Index<Party>>()
         .init_resource::<Index<Camp>>()
+        .init_resource::<TransferTargets>()
         .add_observer(Index::<Party>::on_add)
         .add_observer(Index::<Party>::on_remove)
         .add_observer(Index::<Camp>::on_add)
@@ -40,6 +41,10 @@ impl Plugin for InterfacePlugin {
                 next_state.set(InterfaceState::GameOver)
             },
         )
+        .add_systems(
+            Update,
+            TransferTargets::update.run_if(in_state(SceneState::Active)),
+        )
         .add_systems(
             Update,
             handle_toggle_main_menu
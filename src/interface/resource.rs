@@ -1,5 +1,7 @@
+use crate::{action::TransferPartners, input::SelectedIndex};
 use bevy::prelude::*;
-use std::marker::PhantomData;
+use smallvec::SmallVec;
+use std::{collections::HashMap, marker::PhantomData};
 
 #[derive(Resource, Default)]
 pub struct Index<T: Component>(pub Vec<Entity>, PhantomData<T>);
@@ -13,3 +15,28 @@ impl<T: Component> Index<T> {
         index.0.retain(|&e| e != trigger.target());
     }
 }
+
+/// Inventories that each of the selected parties and camps can transfer items with.
+#[derive(Resource, Default, PartialEq)]
+pub struct TransferTargets(HashMap<Entity, SmallVec<[Entity; 4]>>);
+
+impl TransferTargets {
+    pub fn get(&self, entity: Entity) -> &[Entity] {
+        self.0.get(&entity).map_or(&[], |partners| partners)
+    }
+
+    pub fn update(
+        selected_index: Res<SelectedIndex>,
+        transfer_partners: TransferPartners,
+        mut transfer_targets: ResMut<Self>,
+    ) {
+        transfer_targets.set_if_neq(Self(
+            selected_index
+                .0
+                .iter()
+                .map(|&entity| (entity, transfer_partners.get(entity)))
+                .filter(|(_, partners)| !partners.is_empty())
+                .collect(),
+        ));
+    }
+}
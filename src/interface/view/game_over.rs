@@ -96,7 +96,7 @@ impl ViewTemplate for GameOverDisplay {
                     .style(style_detail_text_red)
                     .children(format!(
                         "Crystals: {}",
-                        match score.dead {
+                        match score.crystals {
                             0 => String::from("None"),
                             v => format!("{}", v),
                         }
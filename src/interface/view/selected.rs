@@ -1,13 +1,21 @@
 use super::super::{
     color::*,
-    component::{CampDetails, CharacterList, PartyDetails},
+    component::{CampDetails, CharacterList, InventoryPanel, PartyDetails},
     prelude::*,
     styles::style_icon,
-    widget::{Button, Opt},
+    widget::{Button, Opt, Tooltip},
     InterfaceAssets,
 };
-use crate::{actor::Party, input::SelectedIndex, structure::Camp};
+use super::shell::TooltipContent;
+use crate::{
+    actor::Party,
+    assets::CodexAssets,
+    input::SelectedIndex,
+    locale::Locale,
+    structure::{Camp, Structure, StructureId},
+};
 use bevy::ecs::world::DeferredWorld;
+use expl_codex::Codex;
 
 fn style_selected_display(style: &mut StyleBuilder) {
     style
@@ -84,6 +92,29 @@ impl ViewTemplate for SelectedTabHeaderIcon {
         let brutal_helm_icon = assets.brutal_helm_icon.clone();
         let campfire_icon = assets.campfire_icon.clone();
 
+        let locale = cx.use_resource::<Locale>();
+        let codex_assets = cx.use_resource::<CodexAssets>();
+        let structure_codex = cx
+            .use_resource::<Assets<Codex<Structure>>>()
+            .get(&codex_assets.structure_codex);
+        let tooltip_text = match selected_type {
+            SelectedType::Party => cx
+                .use_component::<Party>(target)
+                .map(|party| party.name.clone()),
+            SelectedType::Camp => cx
+                .use_component::<StructureId>(target)
+                .zip(structure_codex)
+                .map(|(structure_id, structure_codex)| {
+                    let name = locale.name(structure_codex, &structure_id.0);
+                    match locale.description(structure_codex, &structure_id.0) {
+                        Some(description) => format!("{}\n{}", name, description),
+                        None => name,
+                    }
+                }),
+            SelectedType::Other => None,
+        }
+        .unwrap_or_default();
+
         cx.create_observer(
             move |_click: Trigger<Pointer<Click>>, mut world: DeferredWorld| {
                 focused.set(&mut world, Some(target));
@@ -106,6 +137,7 @@ impl ViewTemplate for SelectedTabHeaderIcon {
                     _ => brutal_helm_icon.clone(),
                 })
                 .style(style_icon)
+                .tooltip(Tooltip::new().children(TooltipContent::new(tooltip_text)))
                 .style_dyn(
                     move |focused, sb| {
                         sb.border(Val::Px(2.0))
@@ -171,6 +203,7 @@ impl ViewTemplate for SelectedView {
                             SelectedTabHeaderIcon::new(target, focused)
                         })),
                     Opt::new(focused_entity.map(SelectedTabViewContent::new)),
+                    Opt::new(focused_entity.map(InventoryPanel::new)),
                 )),
             CharacterList,
         ))
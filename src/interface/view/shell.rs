@@ -8,9 +8,14 @@ use super::super::{
 };
 use super::SelectedView;
 use crate::{
-    input::{Action, ActionState, InputMap, MapHover},
-    turn::Turn,
+    assets::CodexAssets,
+    input::{Action, ActionAvailability, ActionState, InputMap, MapHover},
+    locale::Locale,
+    scene::{LivingCharacters, SafeHavenCrystals},
+    terrain::{Terrain, TerrainId},
+    turn::{Period, Turn},
 };
+use expl_codex::Codex;
 use expl_map::MapPosition;
 
 fn style_toolbar(style: &mut StyleBuilder) {
@@ -25,6 +30,14 @@ fn style_tooltip_text(style: &mut StyleBuilder) {
         .color(css::WHITE);
 }
 
+fn style_blocked_text(style: &mut StyleBuilder) {
+    style
+        .margin(Val::Px(2.0))
+        .font(DEFAULT_FONT)
+        .font_size(16.0)
+        .color(css::SALMON);
+}
+
 fn style_keybind_text(style: &mut StyleBuilder) {
     style
         .margin(Val::Px(2.0))
@@ -49,7 +62,7 @@ fn style_bar(style: &mut StyleBuilder) {
 
 fn style_zone_display(style: &mut StyleBuilder) {
     style
-        .width(Val::Px(100.0))
+        .width(Val::Px(300.0))
         .justify_content(JustifyContent::End);
 }
 
@@ -57,6 +70,18 @@ fn style_zone_display_text(style: &mut StyleBuilder) {
     style.font(DEFAULT_FONT).font_size(32.0).color(css::WHITE);
 }
 
+fn style_expedition_display(style: &mut StyleBuilder) {
+    style
+        .align_self(AlignSelf::FlexEnd)
+        .flex_direction(FlexDirection::Column)
+        .align_items(AlignItems::End)
+        .margin_left(Val::Auto)
+        .margin_right(Val::Px(10.0))
+        .font(DEFAULT_FONT)
+        .font_size(24.0)
+        .color(css::WHITE);
+}
+
 fn style_next_turn_button(style: &mut StyleBuilder) {
     style
         .width(Val::Px(200.0))
@@ -88,8 +113,14 @@ impl KeybindText {
 
     fn keybind_text(&self) -> &'static str {
         match self.keybind {
+            KeyCode::KeyA => "<A>",
             KeyCode::KeyC => "<C>",
+            KeyCode::KeyE => "<E>",
+            KeyCode::KeyF => "<F>",
+            KeyCode::KeyH => "<H>",
             KeyCode::KeyM => "<M>",
+            KeyCode::KeyR => "<R>",
+            KeyCode::KeyS => "<S>",
             KeyCode::Enter => "<Enter>",
             _ => "-",
         }
@@ -106,17 +137,34 @@ impl ViewTemplate for KeybindText {
     }
 }
 
+#[derive(Clone, PartialEq)]
+pub struct BlockedText {
+    reason: String,
+}
+
+impl ViewTemplate for BlockedText {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<Node>::new()
+            .style(style_blocked_text)
+            .children(self.reason.clone())
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct TooltipContent {
     tooltip_text: String,
     keybind: Option<KeyCode>,
+    blocked: Option<String>,
 }
 
 impl TooltipContent {
-    fn new(tooltip_text: impl Into<String>) -> Self {
+    pub fn new(tooltip_text: impl Into<String>) -> Self {
         TooltipContent {
             tooltip_text: tooltip_text.into(),
             keybind: None,
+            blocked: None,
         }
     }
 
@@ -124,6 +172,11 @@ impl TooltipContent {
         self.keybind = keybind;
         self
     }
+
+    fn maybe_blocked(mut self, blocked: Option<String>) -> Self {
+        self.blocked = blocked;
+        self
+    }
 }
 
 impl ViewTemplate for TooltipContent {
@@ -133,6 +186,7 @@ impl ViewTemplate for TooltipContent {
         Element::<Node>::new().style(style_tooltip_text).children((
             self.tooltip_text.clone(),
             Opt::new(self.keybind.map(KeybindText::new)),
+            Opt::new(self.blocked.clone().map(|reason| BlockedText { reason })),
         ))
     }
 }
@@ -172,6 +226,10 @@ impl ViewTemplate for ToolbarItem {
         let action = self.action;
         let inputmap = cx.use_resource::<InputMap<Action>>();
         let keybind = get_keybind_for_action(inputmap, &action);
+        let blocked = cx
+            .use_resource::<ActionAvailability>()
+            .blocked(action)
+            .map(ToString::to_string);
         Button::new()
             .on_click(
                 cx.create_callback(move |mut action_state: ResMut<ActionState<Action>>| {
@@ -181,9 +239,12 @@ impl ViewTemplate for ToolbarItem {
             )
             .style(style_icon)
             .icon(icon.clone())
+            .disabled(blocked.is_some())
             .tooltip(
                 Tooltip::new().position(TooltipPosition::Below).children(
-                    TooltipContent::new(self.tooltip_text.clone()).maybe_keybind(keybind),
+                    TooltipContent::new(self.tooltip_text.clone())
+                        .maybe_keybind(keybind)
+                        .maybe_blocked(blocked),
                 ),
             )
     }
@@ -201,6 +262,9 @@ impl ViewTemplate for Toolbar {
             ToolbarItem::for_action(Action::ResumeMove)
                 .icon(assets.arrow_icon.clone())
                 .tooltip_text("Resume move"),
+            ToolbarItem::for_action(Action::AutoExplore)
+                .icon(assets.footsteps_icon.clone())
+                .tooltip_text("Toggle auto-explore"),
             ToolbarItem::for_action(Action::Camp)
                 .icon(assets.campfire_icon.clone())
                 .tooltip_text("Make/Enter camp"),
@@ -219,6 +283,21 @@ impl ViewTemplate for Toolbar {
             ToolbarItem::for_action(Action::CollectCrystals)
                 .icon(assets.crystals_icon.clone())
                 .tooltip_text("Collect cyrstals"),
+            ToolbarItem::for_action(Action::Forage)
+                .icon(assets.berries_icon.clone())
+                .tooltip_text("Forage for supplies"),
+            ToolbarItem::for_action(Action::Hunt)
+                .icon(assets.gladius_icon.clone())
+                .tooltip_text("Hunt for supplies"),
+            ToolbarItem::for_action(Action::Scout)
+                .icon(assets.person_icon.clone())
+                .tooltip_text("Scout the surroundings"),
+            ToolbarItem::for_action(Action::Attack)
+                .icon(assets.swords_icon.clone())
+                .tooltip_text("Attack enemies"),
+            ToolbarItem::for_action(Action::Retreat)
+                .icon(assets.heart_shield_icon.clone())
+                .tooltip_text("Retreat from combat"),
             ToolbarItem::for_action(Action::OpenPortal)
                 .icon(assets.magic_swirl_icon.clone())
                 .tooltip_text("Open portal"),
@@ -237,14 +316,25 @@ impl ViewTemplate for ZoneDisplay {
 
     fn create(&self, cx: &mut Cx) -> Self::View {
         let map_hover = cx.use_resource::<MapHover>();
-        let text = if let Some(map_position) = map_hover
+        let locale = cx.use_resource::<Locale>();
+        let codex_assets = cx.use_resource::<CodexAssets>();
+        let terrain_codex = cx
+            .use_resource::<Assets<Codex<Terrain>>>()
+            .get(&codex_assets.terrain_codex);
+        let text = map_hover
             .zone
-            .and_then(|e| cx.use_component_untracked::<MapPosition>(e))
-        {
-            format!("Zone: {}", map_position.0)
-        } else {
-            String::from("")
-        };
+            .and_then(|zone| {
+                let map_position = cx.use_component_untracked::<MapPosition>(zone)?;
+                let terrain_name = cx
+                    .use_component_untracked::<TerrainId>(zone)
+                    .zip(terrain_codex)
+                    .map(|(terrain_id, terrain_codex)| locale.name(terrain_codex, &terrain_id.0));
+                Some(match terrain_name {
+                    Some(terrain_name) => format!("{} {}", terrain_name, map_position.0),
+                    None => format!("Zone: {}", map_position.0),
+                })
+            })
+            .unwrap_or_default();
         Element::<Node>::new()
             .named("Zone Display")
             .style((style_zone_display, style_zone_display_text))
@@ -252,6 +342,27 @@ impl ViewTemplate for ZoneDisplay {
     }
 }
 
+#[derive(Clone, PartialEq)]
+struct ExpeditionDisplay;
+
+impl ViewTemplate for ExpeditionDisplay {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let period = cx.use_resource::<Period>();
+        let living = cx.use_resource::<LivingCharacters>();
+        let crystals = cx.use_resource::<SafeHavenCrystals>();
+        Element::<Node>::new()
+            .named("Expedition Display")
+            .style(style_expedition_display)
+            .children((
+                Element::<Node>::new().children(format!("{:?}", period)),
+                Element::<Node>::new().children(format!("Alive: {}", **living)),
+                Element::<Node>::new().children(format!("Crystals: {}", **crystals)),
+            ))
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct NextTurnButton;
 
@@ -313,7 +424,7 @@ impl ViewTemplate for ShellView {
                 Element::<Node>::new()
                     .named("Bottom")
                     .style(style_bar)
-                    .children((SelectedView, NextTurnButton)),
+                    .children((SelectedView, ExpeditionDisplay, NextTurnButton)),
             ))
     }
 }
@@ -1,5 +1,5 @@
 use super::super::{
-    color::{HOVERED, NORMAL, PRESSED},
+    color::{DISABLED, HOVERED, NORMAL, PRESSED},
     styles::style_button,
 };
 use super::Tooltip;
@@ -20,6 +20,14 @@ pub fn style_button_interaction(interaction: Interaction, style: &mut StyleBuild
     });
 }
 
+fn style_button_state((interaction, disabled): (Interaction, bool), style: &mut StyleBuilder) {
+    if disabled {
+        style.background_color(DISABLED);
+    } else {
+        style_button_interaction(interaction, style);
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Button<E: EffectTuple = ()> {
     pub entity: Option<Entity>,
@@ -28,6 +36,7 @@ pub struct Button<E: EffectTuple = ()> {
     pub style: StyleHandle,
     pub tooltip: Option<Tooltip>,
     pub icon: Option<Handle<Image>>,
+    pub disabled: bool,
     pub effects: E,
 }
 
@@ -40,6 +49,7 @@ impl Button<()> {
             style: StyleHandle::default(),
             tooltip: None,
             icon: None,
+            disabled: false,
             effects: (),
         }
     }
@@ -92,6 +102,12 @@ impl<E: EffectTuple> Button<E> {
         self
     }
 
+    /// A disabled button ignores clicks but still shows its tooltip.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
     pub fn add_effect<E1: EntityEffect>(self, effect: E1) -> Button<<E as AppendEffect<E1>>::Result>
     where
         E: AppendEffect<E1>,
@@ -103,6 +119,7 @@ impl<E: EffectTuple> Button<E> {
             style: self.style,
             tooltip: self.tooltip,
             icon: self.icon,
+            disabled: self.disabled,
             effects: self.effects.append_effect(effect),
         }
     }
@@ -118,7 +135,7 @@ impl<E: EffectTuple + 'static> ViewTemplate for Button<E> {
             .cloned()
             .unwrap_or_default();
         let icon = self.icon.clone();
-        let on_click = self.on_click;
+        let on_click = self.on_click.filter(|_| !self.disabled);
         let tooltip: ViewChild = self.tooltip.clone().map_or_else(
             || ().into_view_child(),
             |tooltip| tooltip.parent(id).into_view_child(),
@@ -144,7 +161,7 @@ impl<E: EffectTuple + 'static> ViewTemplate for Button<E> {
                 },
                 icon,
             )
-            .style_dyn(style_button_interaction, interaction)
+            .style_dyn(style_button_state, (interaction, self.disabled))
             .add_effect(self.effects.clone())
             .children((self.children.clone(), tooltip))
     }
@@ -13,8 +13,10 @@ pub struct Inventory {
 }
 
 impl Inventory {
-    pub const CRYSTAL: Id<Item> = Id::from_tag("crystal");
-    pub const SUPPLY: Id<Item> = Id::from_tag("supply");
+    pub const CRYSTAL_TAG: &'static str = "crystal";
+    pub const SUPPLY_TAG: &'static str = "supply";
+    pub const CRYSTAL: Id<Item> = Id::from_tag(Self::CRYSTAL_TAG);
+    pub const SUPPLY: Id<Item> = Id::from_tag(Self::SUPPLY_TAG);
 
     pub fn has_item(&self, item_id: Id<Item>) -> bool {
         self.slots
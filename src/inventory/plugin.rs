@@ -7,6 +7,11 @@ pub struct InventoryPlugin;
 
 impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
+        // Items are not defined by a codex so make their tags known for debug output.
+        for tag in [Inventory::CRYSTAL_TAG, Inventory::SUPPLY_TAG] {
+            Id::<Item>::register_tag(tag);
+        }
+
         app.register_type::<Id<Item>>()
             .register_type::<HashMap<Id<Item>, u32>>()
             .register_type::<Inventory>();
@@ -0,0 +1,172 @@
+use super::entity::{resolve, to_stable};
+use crate::{
+    action::{GameAction, GameActionType},
+    inventory::Item,
+    turn::{Turn, TurnState},
+    ExplError,
+};
+use bevy::prelude::*;
+use expl_codex::Id;
+use expl_map::HexCoord;
+use expl_wfc::Seed;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, fs, path::Path};
+
+/// Kind of presence, used to tell apart the presences that share a zone.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PresenceRole {
+    Party,
+    Camp,
+    Enemy,
+    Structure,
+}
+
+/// Order in which a presence was placed on a map, which a replay repeats.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PresenceSequence(pub(super) u64);
+
+/// Reference to an entity that stays the same when a game is replayed from its seed.
+///
+/// Presences sharing a zone and role are told apart by their [`PresenceSequence`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StableEntity {
+    Zone {
+        position: HexCoord,
+    },
+    Presence {
+        position: HexCoord,
+        role: PresenceRole,
+        index: usize,
+    },
+    Member {
+        group: Box<StableEntity>,
+        index: usize,
+    },
+    SafeHaven,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub turn: u32,
+    pub turn_state: TurnState,
+    pub action_type: GameActionType,
+    pub source: StableEntity,
+    #[serde(default)]
+    pub targets: Vec<StableEntity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<(Id<Item>, u32)>,
+}
+
+impl JournalEntry {
+    /// Find the entities the entry refers to on `map` and rebuild the action.
+    pub fn resolve(&self, world: &World, map: Entity) -> Option<GameAction> {
+        let source = resolve(world, map, &self.source)?;
+        let targets = self
+            .targets
+            .iter()
+            .map(|target| resolve(world, map, target))
+            .collect::<Option<Vec<_>>>()?;
+        Some(
+            GameAction::new(self.action_type, source, targets)
+                .with_items(self.items.iter().copied()),
+        )
+    }
+}
+
+/// Every action applied since the game was generated from `seed`.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[reflect(opaque)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct Journal {
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<JournalEntry>,
+    #[serde(skip)]
+    pending: Option<JournalEntry>,
+}
+
+impl Journal {
+    pub fn new(seed: Seed) -> Self {
+        Self {
+            seed: Some(seed.to_string()),
+            ..default()
+        }
+    }
+
+    /// Remember `action` before it is applied, while its entities can still be found.
+    pub fn record(world: &mut World, action: &GameAction) {
+        if world
+            .get_resource::<Journal>()
+            .is_none_or(|journal| journal.seed.is_none())
+        {
+            return;
+        }
+        let Some((source, targets)) = to_stable(world, action.source()).zip(
+            action
+                .targets()
+                .iter()
+                .map(|&target| to_stable(world, target))
+                .collect::<Option<Vec<_>>>(),
+        ) else {
+            warn!("Could not journal {:?}", action);
+            return;
+        };
+        let entry = JournalEntry {
+            turn: world.get_resource::<Turn>().map_or(0, |turn| turn.number),
+            turn_state: world
+                .get_resource::<State<TurnState>>()
+                .map(|turn_state| *turn_state.get())
+                .unwrap_or_default(),
+            action_type: action.action_type(),
+            source,
+            targets,
+            items: action.items().to_vec(),
+        };
+        if let Some(mut journal) = world.get_resource_mut::<Journal>() {
+            journal.pending = Some(entry);
+        }
+    }
+
+    /// Add the recorded action to the journal once it has been resolved.
+    pub fn commit(&mut self) {
+        if let Some(entry) = self.pending.take() {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Forget the recorded action if it failed.
+    pub fn discard(&mut self) {
+        self.pending = None;
+    }
+
+    /// Remove the last action, for when it has been undone.
+    pub fn undo(&mut self) {
+        self.entries.pop();
+    }
+}
+
+/// Journal entries that are still to be fed to the action queue.
+#[derive(Resource, Debug)]
+pub struct Replay {
+    seed: Seed,
+    pub(super) entries: VecDeque<JournalEntry>,
+}
+
+impl Replay {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ExplError> {
+        Self::from_journal(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn from_journal(journal: Journal) -> Result<Self, ExplError> {
+        let seed = journal.seed.ok_or(ExplError::MissingSeed)?.parse()?;
+        Ok(Self {
+            seed,
+            entries: journal.entries.into(),
+        })
+    }
+
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+}
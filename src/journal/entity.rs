@@ -0,0 +1,92 @@
+use super::component::{PresenceRole, PresenceSequence, StableEntity};
+use crate::{
+    actor::{Enemy, Group, Members, Party},
+    structure::{Camp, SafeHaven, StructureId},
+};
+use bevy::{ecs::world::EntityRef, prelude::*};
+use expl_map::{HexCoord, MapPosition, MapPresence, OnMap, PresenceLayer, ZoneLayer};
+
+fn presence_role(entity: EntityRef) -> Option<PresenceRole> {
+    if entity.contains::<Party>() {
+        Some(PresenceRole::Party)
+    } else if entity.contains::<Camp>() {
+        Some(PresenceRole::Camp)
+    } else if entity.contains::<Enemy>() {
+        Some(PresenceRole::Enemy)
+    } else if entity.contains::<StructureId>() {
+        Some(PresenceRole::Structure)
+    } else {
+        None
+    }
+}
+
+/// The presences at `position` with `role` in the order they were placed on the map.
+fn presence_with_role(
+    world: &World,
+    presence_layer: &PresenceLayer,
+    position: HexCoord,
+    role: PresenceRole,
+) -> Vec<Entity> {
+    let mut presence: Vec<Entity> = presence_layer
+        .presence(position)
+        .copied()
+        .filter(|&e| world.get_entity(e).ok().and_then(presence_role) == Some(role))
+        .collect();
+    presence.sort_by_key(|&e| world.get::<PresenceSequence>(e).copied());
+    presence
+}
+
+pub(super) fn to_stable(world: &World, entity: Entity) -> Option<StableEntity> {
+    let entity_ref = world.get_entity(entity).ok()?;
+    if let Some(&MapPosition(position)) = entity_ref.get::<MapPosition>() {
+        return Some(StableEntity::Zone { position });
+    }
+    if entity_ref.contains::<SafeHaven>() {
+        return Some(StableEntity::SafeHaven);
+    }
+    if let Some((presence, &OnMap(map))) = entity_ref
+        .get::<MapPresence>()
+        .zip(entity_ref.get::<OnMap>())
+    {
+        let role = presence_role(entity_ref)?;
+        let presence_layer = world.get::<PresenceLayer>(map)?;
+        let index = presence_with_role(world, presence_layer, presence.position, role)
+            .iter()
+            .position(|&e| e == entity)?;
+        return Some(StableEntity::Presence {
+            position: presence.position,
+            role,
+            index,
+        });
+    }
+    let group = entity_ref.get::<Group>()?.get();
+    let index = world
+        .get::<Members>(group)?
+        .iter()
+        .position(|&e| e == entity)?;
+    Some(StableEntity::Member {
+        group: Box::new(to_stable(world, group)?),
+        index,
+    })
+}
+
+pub(super) fn resolve(world: &World, map: Entity, stable: &StableEntity) -> Option<Entity> {
+    match stable {
+        StableEntity::Zone { position } => world.get::<ZoneLayer>(map)?.get(*position).copied(),
+        StableEntity::Presence {
+            position,
+            role,
+            index,
+        } => presence_with_role(world, world.get::<PresenceLayer>(map)?, *position, *role)
+            .get(*index)
+            .copied(),
+        StableEntity::Member { group, index } => {
+            let group = resolve(world, map, group)?;
+            world.get::<Members>(group)?.get(*index).copied()
+        }
+        StableEntity::SafeHaven => world
+            .try_query_filtered::<Entity, With<SafeHaven>>()?
+            .iter(world)
+            .next(),
+    }
+}
@@ -0,0 +1,286 @@
+mod component;
+mod entity;
+mod plugin;
+mod system;
+
+pub use component::*;
+pub use plugin::JournalPlugin;
+pub use system::journal_location;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        entity::*,
+        system::{feed_replay, number_presence},
+        *,
+    };
+    use crate::{
+        action::{
+            apply_action, ActionPlugin, ActionPoints, GameAction, GameActionQueue, GameActionType,
+        },
+        actor::{Character, Enemy, GroupCommandsExt, Members, Party},
+        assets::MainAssets,
+        combat::{combat_round, CombatPlugin},
+        creature::{Attack, Health},
+        inventory::Inventory,
+        map_generator::GameRng,
+        structure::SafeHaven,
+        test_fixture::{self, spawn_test_map},
+        turn::{Turn, TurnState},
+    };
+    use bevy::prelude::*;
+    use expl_hexgrid::HexCoord;
+    use expl_map::{MapEvent, MapPresence, OnMap, PresenceLayer, ZoneLayer};
+    use expl_wfc::{Seed, SeedType};
+    use rstest::*;
+
+    #[fixture]
+    fn world() -> (World, Entity) {
+        let mut world = World::new();
+        world.add_observer(number_presence);
+        let map = spawn_test_map(&mut world);
+        (world, map)
+    }
+
+    fn spawn_party(world: &mut World, map: Entity, position: HexCoord) -> Entity {
+        let party = world
+            .spawn((
+                Party::default(),
+                Members::default(),
+                OnMap(map),
+                MapPresence { position },
+            ))
+            .id();
+        world
+            .get_mut::<PresenceLayer>(map)
+            .unwrap()
+            .add_presence(position, party);
+        party
+    }
+
+    #[rstest]
+    fn zone_round_trip(world: (World, Entity)) {
+        let (world, map) = world;
+        let position = HexCoord::new(1, 1);
+        let zone = *world.get::<ZoneLayer>(map).unwrap().get(position).unwrap();
+        let stable = to_stable(&world, zone).unwrap();
+        assert_eq!(stable, StableEntity::Zone { position });
+        assert_eq!(resolve(&world, map, &stable), Some(zone));
+    }
+
+    #[rstest]
+    fn parties_on_same_zone(world: (World, Entity)) {
+        let (mut world, map) = world;
+        let position = HexCoord::new(1, 1);
+        let first = spawn_party(&mut world, map, position);
+        let second = spawn_party(&mut world, map, position);
+        let stable = to_stable(&world, second).unwrap();
+        assert_eq!(
+            stable,
+            StableEntity::Presence {
+                position,
+                role: PresenceRole::Party,
+                index: 1,
+            }
+        );
+        assert_eq!(resolve(&world, map, &stable), Some(second));
+        assert_eq!(
+            resolve(&world, map, &to_stable(&world, first).unwrap()),
+            Some(first)
+        );
+    }
+
+    #[rstest]
+    fn parties_in_placement_order(world: (World, Entity)) {
+        let (mut world, map) = world;
+        // The first party reuses a freed entity index, which sorts it after the second party.
+        let freed = world.spawn_empty().id();
+        world.despawn(freed);
+        let position = HexCoord::new(1, 1);
+        let first = spawn_party(&mut world, map, position);
+        let second = spawn_party(&mut world, map, position);
+        assert!(first > second);
+        assert_eq!(
+            to_stable(&world, first),
+            Some(StableEntity::Presence {
+                position,
+                role: PresenceRole::Party,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            resolve(&world, map, &to_stable(&world, second).unwrap()),
+            Some(second)
+        );
+    }
+
+    #[rstest]
+    fn member_round_trip(world: (World, Entity)) {
+        let (mut world, map) = world;
+        let party = spawn_party(&mut world, map, HexCoord::ZERO);
+        let members = [world.spawn_empty().id(), world.spawn_empty().id()];
+        world.commands().entity(party).add_members(&members);
+        world.flush();
+        let stable = to_stable(&world, members[1]).unwrap();
+        assert_eq!(
+            stable,
+            StableEntity::Member {
+                group: Box::new(StableEntity::Presence {
+                    position: HexCoord::ZERO,
+                    role: PresenceRole::Party,
+                    index: 0,
+                }),
+                index: 1,
+            }
+        );
+        assert_eq!(resolve(&world, map, &stable), Some(members[1]));
+    }
+
+    #[rstest]
+    fn safe_haven_round_trip(world: (World, Entity)) {
+        let (mut world, map) = world;
+        let safe_haven = world.spawn(SafeHaven).id();
+        let stable = to_stable(&world, safe_haven).unwrap();
+        assert_eq!(stable, StableEntity::SafeHaven);
+        assert_eq!(resolve(&world, map, &stable), Some(safe_haven));
+    }
+
+    #[test]
+    fn journal_toml_round_trip() {
+        let mut journal = Journal::new(Seed::new(SeedType::Square(3, 3)));
+        journal.entries = vec![
+            JournalEntry {
+                turn: 2,
+                turn_state: TurnState::Player,
+                action_type: GameActionType::Move,
+                source: StableEntity::Presence {
+                    position: HexCoord::new(1, 2),
+                    role: PresenceRole::Party,
+                    index: 0,
+                },
+                targets: vec![StableEntity::Zone {
+                    position: HexCoord::new(1, 3),
+                }],
+                items: vec![],
+            },
+            JournalEntry {
+                turn: 3,
+                turn_state: TurnState::Player,
+                action_type: GameActionType::TransferItems,
+                source: StableEntity::Presence {
+                    position: HexCoord::new(1, 3),
+                    role: PresenceRole::Party,
+                    index: 0,
+                },
+                targets: vec![StableEntity::SafeHaven],
+                items: vec![(Inventory::CRYSTAL, 4)],
+            },
+        ];
+        let parsed: Journal = toml::from_str(&toml::to_string(&journal).unwrap()).unwrap();
+        assert_eq!(parsed.seed, journal.seed);
+        assert_eq!(parsed.entries, journal.entries);
+    }
+
+    const ENEMY_POSITION: HexCoord = HexCoord::new(1, 0);
+
+    /// A game on the test map with a party of two next to an enemy, returning the party.
+    fn combat_game(seed: Seed) -> (App, Entity) {
+        let mut app = test_fixture::app::default();
+        app.add_plugins((ActionPlugin, CombatPlugin, JournalPlugin))
+            .add_event::<MapEvent>()
+            .insert_resource(MainAssets {
+                swords_emblem_icon: default(),
+                map_template: default(),
+                locale: default(),
+            })
+            .insert_resource(GameRng::from(seed))
+            .insert_resource(Journal::new(seed))
+            .insert_resource(Turn { number: 1 })
+            .insert_resource(State::new(TurnState::Player))
+            .init_resource::<NextState<TurnState>>();
+        let world = app.world_mut();
+        let map = world
+            .query_filtered::<Entity, With<ZoneLayer>>()
+            .single(world)
+            .unwrap();
+        let party = spawn_party(world, map, HexCoord::ZERO);
+        world.entity_mut(party).insert(ActionPoints::new(2));
+        let characters = [(); 2].map(|_| {
+            world
+                .spawn((
+                    Character::default(),
+                    ActionPoints::new(2),
+                    Health {
+                        current: 20,
+                        max: 20,
+                    },
+                    Attack { low: 1, high: 6 },
+                ))
+                .id()
+        });
+        world.commands().entity(party).add_members(&characters);
+        let enemy = world
+            .spawn((
+                Enemy,
+                OnMap(map),
+                MapPresence {
+                    position: ENEMY_POSITION,
+                },
+                Health {
+                    current: 20,
+                    max: 20,
+                },
+                Attack { low: 1, high: 6 },
+            ))
+            .id();
+        world
+            .get_mut::<PresenceLayer>(map)
+            .unwrap()
+            .add_presence(ENEMY_POSITION, enemy);
+        world.flush();
+        (app, party)
+    }
+
+    /// Fight a few combat rounds and return everyone's health afterwards.
+    fn fight(world: &mut World) -> Vec<u16> {
+        for _ in 0..8 {
+            world.run_system_cached(combat_round).unwrap();
+        }
+        world
+            .query::<&Health>()
+            .iter(world)
+            .map(|health| health.current)
+            .collect()
+    }
+
+    #[test]
+    fn replay_combat() {
+        let seed = Seed::new(SeedType::Square(3, 3));
+
+        let (mut app, party) = combat_game(seed);
+        let world = app.world_mut();
+        let &zone = world
+            .query::<&ZoneLayer>()
+            .single(world)
+            .unwrap()
+            .get(ENEMY_POSITION)
+            .unwrap();
+        world
+            .resource_mut::<GameActionQueue>()
+            .add(GameAction::new_attack(party, zone));
+        apply_action(world).unwrap();
+        let played = fight(world);
+        assert!(played.iter().sum::<u16>() < 60);
+        let journal = world.remove_resource::<Journal>().unwrap();
+        assert_eq!(journal.entries.len(), 1);
+
+        let (mut app, _) = combat_game(seed);
+        let world = app.world_mut();
+        world.insert_resource(Replay::from_journal(journal).unwrap());
+        while world.contains_resource::<Replay>() {
+            feed_replay(world);
+            apply_action(world).unwrap();
+        }
+        assert_eq!(fight(world), played);
+    }
+}
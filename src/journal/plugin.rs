@@ -0,0 +1,31 @@
+use super::{component::*, system::*};
+use crate::{assets::AssetState, error, scene::SceneState, turn::set_player_turn};
+use bevy::prelude::*;
+
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Journal>()
+            .init_resource::<Journal>()
+            .add_observer(number_presence)
+            .add_systems(
+                Update,
+                (
+                    begin_journal,
+                    write_journal
+                        .map(error::warn)
+                        .run_if(resource_changed::<Journal>)
+                        .after(begin_journal),
+                    start_replay
+                        .run_if(resource_exists::<Replay>)
+                        .run_if(in_state(AssetState::Loaded))
+                        .run_if(in_state(SceneState::Setup)),
+                    feed_replay
+                        .run_if(resource_exists::<Replay>)
+                        .run_if(in_state(SceneState::Active))
+                        .before(set_player_turn),
+                ),
+            );
+    }
+}
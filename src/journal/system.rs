@@ -0,0 +1,102 @@
+use super::component::*;
+use crate::{
+    action::GameActionQueue,
+    map_generator::MapSeed,
+    scene::SceneState,
+    turn::{Turn, TurnState},
+    ExplError,
+};
+use bevy::prelude::*;
+use expl_map::{MapPresence, ZoneLayer};
+use platform_dirs::AppDirs;
+use std::{fs, path::PathBuf};
+
+pub fn journal_location() -> PathBuf {
+    AppDirs::new(Some("explore-game"), true)
+        .map(|appdirs| appdirs.data_dir.join("journal.toml"))
+        .unwrap()
+}
+
+pub fn begin_journal(seed_query: Query<&MapSeed, Added<MapSeed>>, mut journal: ResMut<Journal>) {
+    for seed in &seed_query {
+        *journal = Journal::new(seed.0);
+    }
+}
+
+pub fn number_presence(
+    trigger: Trigger<OnAdd, MapPresence>,
+    mut commands: Commands,
+    mut next: Local<u64>,
+) {
+    commands
+        .entity(trigger.target())
+        .insert(PresenceSequence(*next));
+    *next += 1;
+}
+
+pub fn write_journal(journal: Res<Journal>) -> Result<(), ExplError> {
+    if journal.seed.is_none() {
+        return Ok(());
+    }
+    let path = journal_location();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string(&*journal)?)?;
+    Ok(())
+}
+
+pub fn start_replay(mut scene_state: ResMut<NextState<SceneState>>) {
+    scene_state.set(SceneState::Reset);
+}
+
+/// Queue the next journal entry once the previous action has been applied, and end the player
+/// turn when there are no more entries for it.
+pub fn feed_replay(world: &mut World) {
+    if !world
+        .get_resource::<GameActionQueue>()
+        .is_some_and(|queue| queue.is_empty())
+    {
+        return;
+    }
+    let Some(mut replay) = world.remove_resource::<Replay>() else {
+        return;
+    };
+    let turn = world.get_resource::<Turn>().map_or(0, |turn| turn.number);
+    let Some(turn_state) = world
+        .get_resource::<State<TurnState>>()
+        .map(|turn_state| *turn_state.get())
+    else {
+        world.insert_resource(replay);
+        return;
+    };
+    let map = world
+        .query_filtered::<Entity, With<ZoneLayer>>()
+        .iter(world)
+        .next();
+
+    match replay.entries.front() {
+        None => {
+            info!("Replay finished");
+            return;
+        }
+        Some(entry) if entry.turn < turn => {
+            warn!("Skipping journal entry from past turn {:?}", entry);
+            replay.entries.pop_front();
+        }
+        Some(entry) if entry.turn == turn && entry.turn_state == turn_state => {
+            match map.and_then(|map| entry.resolve(world, map)) {
+                Some(action) => world.resource_mut::<GameActionQueue>().add(action),
+                None => warn!("Could not resolve journal entry {:?}", entry),
+            }
+            replay.entries.pop_front();
+        }
+        Some(_) if turn_state == TurnState::Player => {
+            world
+                .resource_mut::<NextState<TurnState>>()
+                .set(TurnState::System);
+        }
+        Some(_) => {}
+    }
+    world.insert_resource(replay);
+}
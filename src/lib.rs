@@ -14,6 +14,8 @@ pub mod input;
 pub mod inspector;
 pub mod interface;
 pub mod inventory;
+pub mod journal;
+pub mod locale;
 pub mod map_generator;
 pub mod material;
 pub mod path;
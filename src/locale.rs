@@ -0,0 +1,216 @@
+use crate::{
+    assets::{AssetState, CodexAssets, MainAssets},
+    ExplError,
+};
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use expl_codex::{Codex, CodexSource, Id};
+use std::collections::HashMap;
+
+/// Human-facing text keyed on dotted paths, e.g `creature.slime.name`.
+///
+/// The locale file is a TOML document where nested tables are joined into the keys.
+#[derive(Asset, TypePath, Clone, Debug, Default)]
+pub struct LocaleTable(HashMap<String, String>);
+
+impl LocaleTable {
+    pub fn from_toml(table: toml::Table) -> Self {
+        let mut text = HashMap::new();
+        flatten_into(&mut text, "", table);
+        Self(text)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+}
+
+fn flatten_into(text: &mut HashMap<String, String>, prefix: &str, table: toml::Table) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::Table(table) => flatten_into(text, &key, table),
+            toml::Value::String(value) => {
+                text.insert(key, value);
+            }
+            value => {
+                text.insert(key, value.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+
+impl AssetLoader for LocaleLoader {
+    type Asset = LocaleTable;
+    type Settings = ();
+    type Error = ExplError;
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.toml"]
+    }
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(LocaleTable::from_toml(toml::from_str(
+            std::str::from_utf8(&bytes)?,
+        )?))
+    }
+}
+
+/// Codex entries with a name and description that is shown to the player.
+///
+/// Entries may point to specific keys of the locale table, otherwise the text is looked up as
+/// `<codex>.<tag>.name` and `<codex>.<tag>.description`.
+pub trait LocalizedEntry: CodexSource + TypePath + Send + Sync + Sized {
+    fn codex_handle(assets: &CodexAssets) -> &Handle<Codex<Self>>;
+
+    fn name_key(&self) -> Option<&str>;
+
+    fn description_key(&self) -> Option<&str>;
+}
+
+/// Implement [`LocalizedEntry`] for an entry with `name_key` and `description_key` fields that is
+/// loaded into `codex_field` of [`CodexAssets`].
+macro_rules! impl_localized_entry {
+    ($entry:ty, $codex_field:ident) => {
+        impl $crate::locale::LocalizedEntry for $entry {
+            fn codex_handle(
+                assets: &$crate::assets::CodexAssets,
+            ) -> &bevy::asset::Handle<expl_codex::Codex<Self>> {
+                &assets.$codex_field
+            }
+
+            fn name_key(&self) -> Option<&str> {
+                self.name_key.as_deref()
+            }
+
+            fn description_key(&self) -> Option<&str> {
+                self.description_key.as_deref()
+            }
+        }
+    };
+}
+
+pub(crate) use impl_localized_entry;
+
+/// Text of the active language, updated when the locale file is (re)loaded.
+#[derive(Resource, Clone, Debug, Default, Deref)]
+pub struct Locale(LocaleTable);
+
+impl Locale {
+    /// Localized name of a codex entry, falls back to the tag of the entry.
+    pub fn name<Entry: LocalizedEntry>(&self, codex: &Codex<Entry>, id: &Id<Entry>) -> String {
+        let tag = codex.tag_of(id);
+        let text = match codex.get(id).and_then(Entry::name_key) {
+            Some(key) => self.get(key),
+            None => tag.and_then(|tag| self.get(&format!("{}.{}.name", Entry::EXTENSION, tag))),
+        };
+        text.or(tag)
+            .map_or_else(|| format!("{:?}", id), String::from)
+    }
+
+    /// Localized description of a codex entry, if there is one.
+    pub fn description<Entry: LocalizedEntry>(
+        &self,
+        codex: &Codex<Entry>,
+        id: &Id<Entry>,
+    ) -> Option<String> {
+        let text = match codex.get(id).and_then(Entry::description_key) {
+            Some(key) => self.get(key),
+            None => codex
+                .tag_of(id)
+                .and_then(|tag| self.get(&format!("{}.{}.description", Entry::EXTENSION, tag))),
+        };
+        text.map(String::from)
+    }
+}
+
+/// Looks up the localized text of the entries of a codex.
+#[derive(SystemParam)]
+pub struct Localized<'w, Entry: LocalizedEntry> {
+    locale: Res<'w, Locale>,
+    codex_assets: Res<'w, CodexAssets>,
+    codex: Res<'w, Assets<Codex<Entry>>>,
+}
+
+impl<Entry: LocalizedEntry> Localized<'_, Entry> {
+    fn codex(&self) -> Result<&Codex<Entry>, ExplError> {
+        self.codex
+            .get(Entry::codex_handle(&self.codex_assets))
+            .ok_or(ExplError::MissingCodex)
+    }
+
+    pub fn name(&self, id: &Id<Entry>) -> Result<String, ExplError> {
+        Ok(self.locale.name(self.codex()?, id))
+    }
+
+    pub fn description(&self, id: &Id<Entry>) -> Result<Option<String>, ExplError> {
+        Ok(self.locale.description(self.codex()?, id))
+    }
+}
+
+pub fn update_locale(
+    main_assets: Res<MainAssets>,
+    locale_tables: Res<Assets<LocaleTable>>,
+    mut locale: ResMut<Locale>,
+) {
+    if let Some(table) = locale_tables.get(&main_assets.locale) {
+        locale.0 = table.clone();
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LocaleTable>()
+            .init_asset_loader::<LocaleLoader>()
+            .init_resource::<Locale>()
+            .add_systems(OnEnter(AssetState::Loaded), update_locale)
+            .add_systems(
+                Update,
+                update_locale
+                    .run_if(in_state(AssetState::Loaded))
+                    .run_if(on_event::<AssetEvent<LocaleTable>>),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_table_from_toml() {
+        let table = LocaleTable::from_toml(
+            toml::from_str(
+                "
+[creature.slime]
+name = 'Slime'
+description = 'Sticky'
+",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(table.get("creature.slime.name"), Some("Slime"));
+        assert_eq!(table.get("creature.slime.description"), Some("Sticky"));
+        assert_eq!(table.get("creature.slime"), None);
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::{log::LogPlugin, prelude::*, window::PresentMode};
 use clap::Parser;
 use expl_wfc::Seed;
-use explore_game::{map_generator::MapSeed, material::MaterialPlugins, plugins::ExplPlugins};
+use explore_game::{
+    journal::Replay, map_generator::MapSeed, material::MaterialPlugins, plugins::ExplPlugins,
+};
+use std::path::PathBuf;
 
 pub const CLEAR: Color = Color::srgb(0.1, 0.1, 0.1);
 
@@ -9,15 +12,24 @@ pub const CLEAR: Color = Color::srgb(0.1, 0.1, 0.1);
 struct Cli {
     #[arg(long)]
     seed: Option<Seed>,
+    #[arg(long)]
+    replay: Option<PathBuf>,
 }
 
 fn main() {
     let cli = Cli::parse();
+    let replay = cli
+        .replay
+        .map(|path| Replay::from_file(path).expect("could not read journal"));
+    let seed = replay.as_ref().map(Replay::seed).or(cli.seed);
 
-    App::new()
-        .insert_resource(ClearColor(CLEAR))
+    let mut app = App::new();
+    if let Some(replay) = replay {
+        app.insert_resource(replay);
+    }
+    app.insert_resource(ClearColor(CLEAR))
         .add_systems(Startup, move |mut commands: Commands| {
-            if let Some(seed) = cli.seed {
+            if let Some(seed) = seed {
                 commands.spawn(MapSeed(seed));
             }
         })
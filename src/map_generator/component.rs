@@ -3,6 +3,7 @@ use bevy::{prelude::*, tasks::Task};
 use expl_codex::Id;
 use expl_hexgrid::{layout::SquareGridLayout, Grid, HexCoord};
 use expl_wfc::Seed;
+use rand::{rngs::StdRng, Rng, RngCore};
 
 #[derive(Default)]
 pub struct ZonePrototype {
@@ -24,3 +25,54 @@ pub struct GenerateMapTask(pub Task<Result<MapPrototype, ExplError>>);
 
 #[derive(Component)]
 pub struct MapSeed(pub Seed);
+
+/// Source of every gameplay roll, seeded from the map seed so that a replay rolls the same.
+///
+/// A SplitMix64 generator, whose whole state is a single number that is saved with the game.
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self {
+            state: rand::random(),
+        }
+    }
+}
+
+impl From<Seed> for GameRng {
+    fn from(seed: Seed) -> Self {
+        Self {
+            state: StdRng::from(seed).gen(),
+        }
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{asset::*, system::*};
+use super::{asset::*, component::*, system::*};
 use crate::{assets::AssetState, error, scene::SceneState};
 use bevy::prelude::*;
 
@@ -8,9 +8,12 @@ impl Plugin for MapGeneratorPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<MapTemplate>()
             .init_asset_loader::<TemplateLoader>()
+            .register_type::<GameRng>()
+            .init_resource::<GameRng>()
             .add_systems(
                 Update,
                 (
+                    seed_game_rng,
                     start_map_generation
                         .map(error::warn)
                         .run_if(in_state(SceneState::Reset)),
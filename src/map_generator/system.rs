@@ -1,4 +1,4 @@
-use super::{asset::MapTemplate, task::generate_map, GenerateMapTask, MapSeed};
+use super::{asset::MapTemplate, task::generate_map, GameRng, GenerateMapTask, MapSeed};
 use crate::{assets::MainAssets, scene::SceneState, terrain::TerrainCodex, ExplError};
 use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
 use expl_wfc::Seed;
@@ -25,6 +25,12 @@ pub fn start_map_generation(
     Ok(())
 }
 
+pub fn seed_game_rng(seed_query: Query<&MapSeed, Added<MapSeed>>, mut rng: ResMut<GameRng>) {
+    for seed in &seed_query {
+        *rng = GameRng::from(seed.0);
+    }
+}
+
 pub fn watch_map_generation_task(
     mut commands: Commands,
     mut generate_map_task: Query<(Entity, &mut GenerateMapTask)>,
@@ -6,7 +6,7 @@ use bevy::{
         renderer::{RenderDevice, RenderQueue},
     },
 };
-use expl_codex::{Codex, Id};
+use expl_codex::{Codex, CodexChanged, Id};
 use std::marker::PhantomData;
 
 pub trait CodexBufferValue:
@@ -27,7 +27,13 @@ where
 {
     fn build(&self, app: &mut App) {
         app.init_resource::<CodexBuffer<V>>()
-            .add_systems(OnEnter(AssetState::Loaded), prepare_buffer::<V>);
+            .add_systems(OnEnter(AssetState::Loaded), prepare_buffer::<V>)
+            .add_systems(
+                Update,
+                update_buffer::<V>
+                    .run_if(in_state(AssetState::Loaded))
+                    .run_if(on_event::<CodexChanged<V::CodexValue>>),
+            );
     }
 }
 
@@ -90,3 +96,23 @@ fn prepare_buffer<V>(
     }
     buffer.write_buffer(&render_device, &render_queue);
 }
+
+fn update_buffer<V>(
+    mut codex_changed_events: EventReader<CodexChanged<V::CodexValue>>,
+    mut buffer: ResMut<CodexBuffer<V>>,
+    codex_assets: Res<Assets<Codex<V::CodexValue>>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) where
+    V: CodexBufferValue + 'static + Send + Sync,
+    V::CodexValue: Send + Sync + std::fmt::Debug,
+{
+    for event in codex_changed_events.read() {
+        let Some(codex) = codex_assets.get(event.codex) else {
+            continue;
+        };
+        // Removed entries are left in the buffer to keep the indices of other entries stable.
+        buffer.extend(event.updated().map(|id| (id, &codex[id])));
+    }
+    buffer.write_buffer(&render_device, &render_queue);
+}
@@ -16,7 +16,13 @@ impl Plugin for DecorationMaterialPlugin {
             CodexBufferPlugin::<DecorationData>::default(),
             MaterialPlugin::<DecorationMaterial>::default(),
         ))
-        .add_systems(Update, apply_to_material);
+        .add_systems(
+            Update,
+            (
+                apply_to_material,
+                update_decoration_data.run_if(resource_changed::<DecorationBuffer>),
+            ),
+        );
     }
 }
 
@@ -120,3 +126,15 @@ fn apply_to_material(
         material.set_explored(fog.explored);
     }
 }
+
+fn update_decoration_data(
+    mut materials: ResMut<Assets<DecorationMaterial>>,
+    decoration_buffer: Res<DecorationBuffer>,
+) {
+    let Some(buffer) = decoration_buffer.buffer() else {
+        return;
+    };
+    for (_, material) in materials.iter_mut() {
+        material.decoration_data = buffer.clone();
+    }
+}
@@ -16,7 +16,13 @@ impl Plugin for StructureMaterialPlugin {
             CodexBufferPlugin::<StructureData>::default(),
             MaterialPlugin::<StructureMaterial>::default(),
         ))
-        .add_systems(Update, apply_to_material);
+        .add_systems(
+            Update,
+            (
+                apply_to_material,
+                update_structure_data.run_if(resource_changed::<StructureBuffer>),
+            ),
+        );
     }
 }
 
@@ -120,3 +126,15 @@ fn apply_to_material(
         material.set_explored(fog.explored);
     }
 }
+
+fn update_structure_data(
+    mut materials: ResMut<Assets<StructureMaterial>>,
+    structure_buffer: Res<StructureBuffer>,
+) {
+    let Some(buffer) = structure_buffer.buffer() else {
+        return;
+    };
+    for (_, material) in materials.iter_mut() {
+        material.structure_data = buffer.clone();
+    }
+}
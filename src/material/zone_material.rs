@@ -17,7 +17,13 @@ impl Plugin for ZoneMaterialPlugin {
             CodexBufferPlugin::<TerrainData>::default(),
             MaterialPlugin::<ZoneMaterial>::default(),
         ))
-        .add_systems(Update, apply_to_material);
+        .add_systems(
+            Update,
+            (
+                apply_to_material,
+                update_terrain_data.run_if(resource_changed::<TerrainBuffer>),
+            ),
+        );
     }
 }
 
@@ -188,23 +194,15 @@ fn apply_to_material(
     }
 }
 
-/*
-fn update_from_terrain_codex(
-    mut codex_events: EventReader<AssetEvent<Codex<Terrain>>>,
-    terrain_codex_assets: Res<Assets<Codex<Terrain>>>,
-    mut terrain_buffer: ResMut<TerrainBuffer>,
-    render_device: Res<RenderDevice>,
-    render_queue: Res<RenderQueue>,
+/// Point the materials at the current buffer as it may be reallocated when the codex changes.
+fn update_terrain_data(
+    mut zone_materials: ResMut<Assets<ZoneMaterial>>,
+    terrain_buffer: Res<TerrainBuffer>,
 ) {
-    for event in codex_events.read() {
-        let AssetEvent::Modified { id: asset_id } = event else {
-            continue;
-        };
-        let terrain_codex = terrain_codex_assets.get(*asset_id).unwrap();
-        terrain_buffer.extend(terrain_codex.iter());
+    let Some(buffer) = terrain_buffer.buffer() else {
+        return;
+    };
+    for (_, material) in zone_materials.iter_mut() {
+        material.terrain_data = buffer.clone();
     }
-    terrain_buffer
-        .data
-        .write_buffer(&render_device, &render_queue);
 }
-*/
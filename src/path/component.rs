@@ -45,3 +45,8 @@ impl PathGuided {
         self.current
     }
 }
+
+/// Standing order for a party to keep moving to the nearest unexplored zones.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct AutoExplore;
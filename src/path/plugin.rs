@@ -1,14 +1,30 @@
-use super::system::*;
-use crate::assets::AssetState;
+use super::{component::AutoExplore, system::*};
+use crate::{
+    assets::AssetState,
+    turn::{TurnSet, TurnState},
+};
 use bevy::prelude::*;
 
 pub struct PathPlugin;
 
 impl Plugin for PathPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (update_path_display).run_if(in_state(AssetState::Loaded)),
-        );
+        app.register_type::<AutoExplore>()
+            .add_systems(
+                Update,
+                (update_path_display).run_if(in_state(AssetState::Loaded)),
+            )
+            .add_systems(
+                OnEnter(TurnState::Player),
+                queue_auto_explore::<()>.in_set(TurnSet::Effects),
+            )
+            .add_systems(
+                Update,
+                (
+                    stop_auto_explore,
+                    queue_auto_explore::<Added<AutoExplore>>.run_if(in_state(TurnState::Player)),
+                )
+                    .chain(),
+            );
     }
 }
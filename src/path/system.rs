@@ -1,6 +1,15 @@
-use super::{bundle::*, component::*, mesh::update_path_mesh};
-use crate::scene::save::Save;
-use bevy::prelude::*;
+use super::{bundle::*, component::*, mesh::update_path_mesh, system_param::Frontier};
+use crate::{
+    action::{ActionPoints, GameAction, GameActionQueue},
+    actor::Enemy,
+    combat::Combat,
+    scene::save::Save,
+};
+use bevy::{
+    ecs::{entity::EntityHashSet, query::QueryFilter},
+    prelude::*,
+};
+use expl_map::MapPresence;
 use splines::{Interpolation, Key, Spline};
 use std::iter;
 
@@ -63,3 +72,59 @@ pub fn update_path_display(
         }
     }
 }
+
+/// Queue the next move of exploring parties that have action points left.
+pub fn queue_auto_explore<F: QueryFilter>(
+    mut commands: Commands,
+    mut game_action_queue: ResMut<GameActionQueue>,
+    mut party_query: Query<
+        (Entity, &MapPresence, &ActionPoints, &mut PathGuided),
+        (With<AutoExplore>, F),
+    >,
+    frontier: Frontier,
+) {
+    for (entity, presence, action_points, mut path_guided) in &mut party_query {
+        if action_points.current == 0 {
+            continue;
+        }
+        if let Some(next) = frontier.next_step(presence.position, &mut path_guided) {
+            game_action_queue.add(GameAction::new_move(entity, next));
+        } else {
+            info!("Nothing left to explore for {entity}");
+            commands.entity(entity).remove::<AutoExplore>();
+        }
+    }
+}
+
+/// Stop auto-exploring when a new enemy comes into view, or for the parties in or next to a
+/// combat.
+pub fn stop_auto_explore(
+    mut commands: Commands,
+    mut visible_enemies: Local<EntityHashSet>,
+    enemy_query: Query<(Entity, &Visibility), (With<Enemy>, Changed<Visibility>)>,
+    combat_query: Query<&Combat>,
+    mut party_query: Query<(Entity, &MapPresence, &mut PathGuided), With<AutoExplore>>,
+) {
+    let mut spotted = false;
+    for (entity, visibility) in &enemy_query {
+        if *visibility == Visibility::Hidden {
+            visible_enemies.remove(&entity);
+        } else {
+            spotted |= visible_enemies.insert(entity);
+        }
+    }
+    if !spotted && combat_query.is_empty() {
+        return;
+    }
+    for (entity, presence, mut path_guided) in &mut party_query {
+        let near_combat = combat_query
+            .iter()
+            .any(|combat| combat.position().distance(presence.position) <= 1);
+        if !spotted && !near_combat {
+            continue;
+        }
+        info!("Stopping auto-explore for {entity}");
+        path_guided.path([]);
+        commands.entity(entity).remove::<AutoExplore>();
+    }
+}
@@ -1,11 +1,12 @@
+use super::PathGuided;
 use crate::{
     terrain::{Terrain, TerrainCodex, TerrainId},
     ExplError,
 };
 use bevy::{ecs::system::SystemParam, prelude::*};
 use expl_codex::Codex;
-use expl_map::{HexCoord, ZoneLayer};
-use pathfinding::prelude::astar;
+use expl_map::{Fog, HexCoord, ZoneLayer};
+use pathfinding::prelude::{astar, bfs};
 
 #[derive(SystemParam)]
 pub struct PathFinder<'w, 's> {
@@ -36,9 +37,7 @@ impl BoundPathFinder<'_, '_> {
         astar(
             &start,
             |(p, _)| {
-                p.neighbours()
-                    .filter_map(|p| self.zone_layer.get(p).map(|e| (p, *e)))
-                    .filter(|(_, e)| self.is_walkable(*e))
+                self.walkable_neighbours(*p)
                     .map(|r| (r, 1))
                     .collect::<Vec<((HexCoord, Entity), u32)>>()
             },
@@ -48,6 +47,29 @@ impl BoundPathFinder<'_, '_> {
         .map(|(path, _len)| path)
     }
 
+    /// Find a path to the closest zone other than `start` for which `goal` returns true.
+    pub fn find_nearest<F>(&self, start: HexCoord, mut goal: F) -> Option<Vec<(HexCoord, Entity)>>
+    where
+        F: FnMut(HexCoord, Entity) -> bool,
+    {
+        let start = self.zone_layer.get(start).map(|e| (start, *e))?;
+        bfs(
+            &start,
+            |(p, _)| self.walkable_neighbours(*p),
+            |&(p, e)| p != start.0 && goal(p, e),
+        )
+    }
+
+    fn walkable_neighbours(
+        &self,
+        position: HexCoord,
+    ) -> impl Iterator<Item = (HexCoord, Entity)> + '_ {
+        position
+            .neighbours()
+            .filter_map(|p| self.zone_layer.get(p).map(|e| (p, *e)))
+            .filter(|(_, e)| self.is_walkable(*e))
+    }
+
     pub fn is_walkable(&self, entity: Entity) -> bool {
         self.terrain_query
             .get(entity)
@@ -56,11 +78,47 @@ impl BoundPathFinder<'_, '_> {
     }
 }
 
+/// Finds the way to the zones at the edge of what has been explored.
+#[derive(SystemParam)]
+pub struct Frontier<'w, 's> {
+    path_finder: PathFinder<'w, 's>,
+    fog_query: Query<'w, 's, &'static Fog>,
+}
+
+impl Frontier<'_, '_> {
+    fn is_explored(&self, zone: Entity) -> bool {
+        self.fog_query.get(zone).is_ok_and(|fog| fog.explored)
+    }
+
+    /// Path to the nearest reachable explored zone that borders on unexplored zones.
+    pub fn find_path(&self, start: HexCoord) -> Result<Option<Vec<(HexCoord, Entity)>>, ExplError> {
+        let path_finder = self.path_finder.get()?;
+        Ok(path_finder.find_nearest(start, |position, zone| {
+            self.is_explored(zone)
+                && position
+                    .neighbours()
+                    .filter_map(|p| path_finder.zone_layer.get(p))
+                    .any(|&neighbour| !self.is_explored(neighbour))
+        }))
+    }
+
+    /// The next zone for an exploring party to move to, heading for a new frontier once the end
+    /// of the current path is reached. Nothing is returned when there is nothing left to explore.
+    pub fn next_step(&self, position: HexCoord, path_guided: &mut PathGuided) -> Option<Entity> {
+        if path_guided.next().is_none() {
+            let path = self.find_path(position).ok().flatten()?;
+            path_guided.path(path.into_iter().map(|(_, zone)| zone));
+        }
+        path_guided.next().copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{HexCoord, PathFinder};
-    use crate::test_fixture::app;
+    use super::{Frontier, HexCoord, PathFinder};
+    use crate::{terrain::TerrainId, test_fixture::app};
     use bevy::prelude::*;
+    use expl_map::{Fog, ZoneLayer};
     use rstest::*;
 
     #[derive(Component, Debug)]
@@ -86,6 +144,80 @@ mod tests {
         Ok(())
     }
 
+    #[derive(Component, Debug)]
+    struct FrontierPath(Option<Vec<(HexCoord, Entity)>>);
+
+    fn find_frontier_system(
+        mut commands: Commands,
+        frontier: Frontier,
+        params_query: Query<(Entity, &Start)>,
+    ) -> Result {
+        let (entity, start) = params_query.single()?;
+        commands
+            .entity(entity)
+            .insert(FrontierPath(frontier.find_path(start.0)?));
+        Ok(())
+    }
+
+    fn explore_all_but(app: &mut App, unexplored: Option<HexCoord>) {
+        let world = app.world_mut();
+        let zones: Vec<Entity> = world
+            .query_filtered::<Entity, With<TerrainId>>()
+            .iter(world)
+            .collect();
+        for zone in zones {
+            world.entity_mut(zone).insert(Fog {
+                visible: false,
+                explored: true,
+            });
+        }
+        if let Some(position) = unexplored {
+            let zone = *world
+                .query::<&ZoneLayer>()
+                .single(world)
+                .unwrap()
+                .get(position)
+                .unwrap();
+            world.entity_mut(zone).insert(Fog::default());
+        }
+    }
+
+    #[rstest]
+    fn frontier(mut app: App) {
+        explore_all_but(&mut app, Some(HexCoord::new(0, 2)));
+        app.world_mut().spawn(Start(HexCoord::new(0, 0)));
+        app.add_systems(Update, find_frontier_system);
+
+        app.update();
+
+        let path = app
+            .world_mut()
+            .query::<&FrontierPath>()
+            .single(app.world())
+            .unwrap()
+            .0
+            .clone()
+            .unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last().unwrap().0.distance(HexCoord::new(0, 2)), 1);
+    }
+
+    #[rstest]
+    fn frontier_all_explored(mut app: App) {
+        explore_all_but(&mut app, None);
+        app.world_mut().spawn(Start(HexCoord::new(0, 0)));
+        app.add_systems(Update, find_frontier_system);
+
+        app.update();
+
+        let frontier_path = app
+            .world_mut()
+            .query::<&FrontierPath>()
+            .single(app.world())
+            .unwrap();
+        assert!(frontier_path.0.is_none());
+    }
+
     #[rstest]
     fn pathfinding_neighbour(mut app: App) {
         app.world_mut()
@@ -2,9 +2,9 @@ use crate::{
     action::ActionPlugin, actor::ActorPlugin, assets::AssetsPlugin, camera::CameraControlPlugin,
     combat::CombatPlugin, creature::CreaturePlugin, enemy::EnemyPlugin,
     floating_text::FloatingTextPlugin, input::InputPlugin, inspector::InspectorPlugin,
-    interface::InterfacePlugin, inventory::InventoryPlugin, map_generator::MapGeneratorPlugin,
-    path::PathPlugin, scene::ScenePlugin, structure::StructurePlugin, terrain::TerrainPlugin,
-    turn::TurnPlugin,
+    interface::InterfacePlugin, inventory::InventoryPlugin, journal::JournalPlugin,
+    locale::LocalePlugin, map_generator::MapGeneratorPlugin, path::PathPlugin, scene::ScenePlugin,
+    structure::StructurePlugin, terrain::TerrainPlugin, turn::TurnPlugin,
 };
 use bevy::app::{PluginGroup, PluginGroupBuilder};
 
@@ -33,6 +33,8 @@ impl PluginGroup for ExplPlugins {
             .add(InspectorPlugin)
             .add(InterfacePlugin)
             .add(InventoryPlugin)
+            .add(JournalPlugin)
+            .add(LocalePlugin)
             .add(MapGeneratorPlugin)
             .add(PathPlugin)
             .add(ScenePlugin)
@@ -6,4 +6,4 @@ mod score;
 mod world;
 
 pub use plugin::{ScenePlugin, SceneSet, SceneState};
-pub use score::Score;
+pub use score::{Casualties, LivingCharacters, SafeHavenCrystals, SafeHavenSurvivors, Score};
@@ -2,10 +2,12 @@ use crate::{
     assets::AssetState,
     cleanup, error,
     input::{action_just_pressed, Action},
+    journal::Replay,
     turn::{TurnSet, TurnState},
 };
 use bevy::prelude::*;
 use bevy_tweening::{component_animator_system, AnimationSystem, TweeningPlugin};
+use expl_databinding::DataBindingAppExt;
 
 use super::{camera::*, light::*, save::*, score::*, world::*};
 
@@ -20,6 +22,10 @@ impl Plugin for ScenePlugin {
                 moonshine_save::load::LoadPlugin,
                 TweeningPlugin,
             ))
+            .init_aggregate::<SafeHavenCrystals>()
+            .init_aggregate::<SafeHavenSurvivors>()
+            .init_aggregate::<LivingCharacters>()
+            .init_aggregate::<Casualties>()
             .configure_sets(
                 OnEnter(SceneState::Active),
                 (
@@ -32,7 +38,14 @@ impl Plugin for ScenePlugin {
                 )
                     .chain(),
             )
-            .add_systems(Startup, (spawn_camera, spawn_light, load_saved_scene))
+            .add_systems(
+                Startup,
+                (
+                    spawn_camera,
+                    spawn_light,
+                    load_saved_scene.run_if(not(resource_exists::<Replay>)),
+                ),
+            )
             .add_systems(
                 Update,
                 handle_save.run_if(action_just_pressed(Action::Save)),
@@ -55,6 +68,7 @@ impl Plugin for ScenePlugin {
                         .run_if(in_state(SceneState::Active)),
                 ),
             )
+            .add_systems(OnEnter(SceneState::GameOver), record_score)
             .add_systems(
                 OnEnter(TurnState::Player),
                 apply_period_light.map(error::warn).in_set(TurnSet::Effects),
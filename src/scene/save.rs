@@ -1,4 +1,7 @@
-use crate::{action, actor, creature, input, inventory, structure, terrain, turn};
+use crate::{
+    action, actor, creature, input, inventory, journal, map_generator, path, structure, terrain,
+    turn,
+};
 use bevy::prelude::*;
 use expl_map;
 use moonshine_save::{
@@ -42,6 +45,7 @@ pub fn component_filter() -> SceneFilter {
         .allow::<Name>()
         .allow::<Save>()
         .allow::<action::ActionPoints>()
+        .allow::<action::Scouting>()
         .allow::<actor::ActorId>()
         .allow::<actor::Character>()
         .allow::<actor::Enemy>()
@@ -55,23 +59,31 @@ pub fn component_filter() -> SceneFilter {
         .allow::<creature::Health>()
         .allow::<input::Selection>()
         .allow::<inventory::Inventory>()
+        .allow::<expl_map::FactionFog>()
         .allow::<expl_map::Fog>()
         .allow::<expl_map::FogRevealer>()
         .allow::<expl_map::MapLayout>()
         .allow::<expl_map::MapPosition>()
         .allow::<expl_map::MapPresence>()
+        .allow::<expl_map::OnMap>()
+        .allow::<expl_map::RevealerFaction>()
         .allow::<expl_map::ViewRadius>()
+        .allow::<path::AutoExplore>()
         .allow::<structure::Camp>()
         .allow::<structure::Portal>()
         .allow::<structure::SafeHaven>()
         .allow::<structure::Spawner>()
         .allow::<structure::StructureId>()
         .allow::<terrain::CrystalDeposit>()
+        .allow::<terrain::Depletion>()
         .allow::<terrain::TerrainId>()
         .allow::<terrain::ZoneDecorations>()
         .allow::<Transform>()
 }
 
 pub fn resource_filter() -> SceneFilter {
-    SceneFilter::deny_all().allow::<turn::Turn>()
+    SceneFilter::deny_all()
+        .allow::<turn::Turn>()
+        .allow::<journal::Journal>()
+        .allow::<map_generator::GameRng>()
 }
@@ -1,6 +1,12 @@
 use super::SceneState;
-use crate::{actor::Members, creature::Corpse, inventory::Inventory, structure::SafeHaven};
+use crate::{
+    actor::{Character, Members},
+    creature::Corpse,
+    inventory::Inventory,
+    structure::SafeHaven,
+};
 use bevy::prelude::*;
+use expl_databinding::Aggregate;
 use expl_map::MapPresence;
 
 #[derive(Resource, Reflect, Default, Debug)]
@@ -11,22 +17,82 @@ pub struct Score {
     pub crystals: u32,
 }
 
+/// Crystals brought back to the safe haven.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug, Deref)]
+pub struct SafeHavenCrystals(pub u32);
+
+impl Aggregate for SafeHavenCrystals {
+    type Data = &'static Inventory;
+    type Filter = With<SafeHaven>;
+
+    fn aggregate(query: &Query<&Inventory, With<SafeHaven>>) -> Self {
+        Self(
+            query
+                .iter()
+                .map(|inventory| inventory.count_item(Inventory::CRYSTAL))
+                .sum(),
+        )
+    }
+}
+
+/// Characters that made it back to the safe haven.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug, Deref)]
+pub struct SafeHavenSurvivors(pub u32);
+
+impl Aggregate for SafeHavenSurvivors {
+    type Data = &'static Members;
+    type Filter = With<SafeHaven>;
+
+    fn aggregate(query: &Query<&Members, With<SafeHaven>>) -> Self {
+        Self(query.iter().map(|members| members.len() as u32).sum())
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug, Deref)]
+pub struct LivingCharacters(pub u32);
+
+impl Aggregate for LivingCharacters {
+    type Data = Entity;
+    type Filter = (With<Character>, Without<Corpse>);
+
+    fn aggregate(query: &Query<Entity, (With<Character>, Without<Corpse>)>) -> Self {
+        Self(query.iter().count() as u32)
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug, Deref)]
+pub struct Casualties(pub u32);
+
+impl Aggregate for Casualties {
+    type Data = Entity;
+    type Filter = With<Corpse>;
+
+    fn aggregate(query: &Query<Entity, With<Corpse>>) -> Self {
+        Self(query.iter().count() as u32)
+    }
+}
+
 pub fn game_over(
-    mut commands: Commands,
     mut scene_state: ResMut<NextState<SceneState>>,
     group_query: Query<&Members, With<MapPresence>>,
-    safe_haven_query: Query<(&Members, &Inventory), With<SafeHaven>>,
-    corpse_query: Query<&Corpse>,
 ) {
     if group_query.iter().any(|m| !m.is_empty()) {
         return;
     }
-    let mut score = Score::default();
-    for (members, inventory) in &safe_haven_query {
-        score.survivors += members.len() as u32;
-        score.crystals += inventory.count_item(Inventory::CRYSTAL);
-    }
-    score.dead = corpse_query.iter().count() as u32;
-    commands.insert_resource(score);
     scene_state.set(SceneState::GameOver);
 }
+
+/// The aggregates are computed after [`game_over`] in the frame before the transition, so the score
+/// includes what happened in the final turn.
+pub fn record_score(
+    mut commands: Commands,
+    survivors: Res<SafeHavenSurvivors>,
+    casualties: Res<Casualties>,
+    crystals: Res<SafeHavenCrystals>,
+) {
+    commands.insert_resource(Score {
+        survivors: **survivors,
+        dead: **casualties,
+        crystals: **crystals,
+    });
+}
@@ -12,7 +12,9 @@ use crate::{
 use bevy::prelude::*;
 use expl_codex::Id;
 use expl_hexgrid::{layout::GridLayout, HexCoord, Neighbours};
-use expl_map::{MapCommandsExt, MapLayout, MapPosition, MapPresence, PresenceLayer, ZoneLayer};
+use expl_map::{
+    MapCommandsExt, MapLayout, MapPosition, MapPresence, OnMap, PresenceLayer, ZoneLayer,
+};
 use expl_wfc::{Seed, SeedType};
 use std::collections::HashMap;
 
@@ -38,21 +40,36 @@ pub fn cleanup_map_generation_task(
 pub fn fluff_loaded_map(
     mut commands: Commands,
     map_query: Query<(Entity, &MapLayout)>,
-    zone_query: Query<(&MapPosition, Entity), With<TerrainId>>,
-    presence_query: Query<(Entity, &MapPresence), Without<Visibility>>,
+    zone_query: Query<(&MapPosition, &OnMap, Entity), With<TerrainId>>,
+    presence_query: Query<(Entity, &MapPresence, &OnMap), Without<Visibility>>,
 ) -> Result<(), ExplError> {
-    let (entity, &MapLayout(layout)) = map_query.single()?;
-    let zone_lookup: HashMap<HexCoord, _> = zone_query
+    let zone_lookup: HashMap<(Entity, HexCoord), _> = zone_query
         .iter()
-        .map(|(&MapPosition(p), e)| (p, e))
+        .map(|(&MapPosition(p), &OnMap(map), e)| ((map, p), e))
         .collect();
-    let tiles = layout.iter().map(|coord| zone_lookup[&coord]).collect();
-    let zone_layer = ZoneLayer::new(layout, tiles);
-    let mut presence_layer = PresenceLayer::new(layout);
-    for (entity, presence) in &presence_query {
-        presence_layer.add_presence(presence.position, entity);
+    for (map_entity, &MapLayout(layout)) in &map_query {
+        // Saves from before zones belonged to a map have no `OnMap` on the zones.
+        let tiles = layout
+            .iter()
+            .map(|coord| {
+                zone_lookup
+                    .get(&(map_entity, coord))
+                    .copied()
+                    .ok_or(ExplError::MissingZone(coord))
+            })
+            .collect::<Result<_, _>>()?;
+        let zone_layer = ZoneLayer::new(layout, tiles);
+        let mut presence_layer = PresenceLayer::new(layout);
+        for (entity, presence, _) in presence_query
+            .iter()
+            .filter(|(_, _, &OnMap(map))| map == map_entity)
+        {
+            presence_layer.add_presence(presence.position, entity);
+        }
+        commands
+            .entity(map_entity)
+            .insert((zone_layer, presence_layer));
     }
-    commands.entity(entity).insert((zone_layer, presence_layer));
 
     Ok(())
 }
@@ -64,6 +81,7 @@ pub fn spawn_generated_map(
 ) -> Result<(), ExplError> {
     let prototype = map_prototype_query.single()?;
     let void = Id::from_tag("void");
+    let map_entity = commands.spawn((Name::new("Game map"), save::Save)).id();
     let tiles = prototype
         .tiles
         .iter()
@@ -79,6 +97,7 @@ pub fn spawn_generated_map(
             let mut zone = commands.spawn((
                 Name::new(format!("Zone {}", position)),
                 save::Save,
+                OnMap(map_entity),
                 zone_bundle,
             ));
             zone.attach_role(zone_role);
@@ -90,9 +109,7 @@ pub fn spawn_generated_map(
             zone.id()
         })
         .collect();
-    commands.spawn((
-        Name::new("Game map"),
-        save::Save,
+    commands.entity(map_entity).insert((
         MapLayout(prototype.tiles.layout),
         ZoneLayer::new(prototype.tiles.layout, tiles),
         PresenceLayer::new(prototype.tiles.layout),
@@ -1,18 +1,22 @@
+use crate::locale::impl_localized_entry;
 use bevy::{asset::LoadContext, prelude::*};
-use expl_codex::{CodexSource, FromWithLoadContext};
+use expl_codex::{AssetReference, CodexSource, FromWithLoadContext};
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Default, Deserialize)]
-pub(super) struct RawStructure {
+#[derive(Clone, Debug, Default, Deserialize, Reflect)]
+pub struct RawStructure {
     color_a: Color,
     color_b: Color,
     color_c: Color,
+    #[reflect(@AssetReference)]
     mesh: String,
     scale: f32,
     rotation: f32,
+    name_key: Option<String>,
+    description_key: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, TypePath)]
+#[derive(Clone, Debug, Default, PartialEq, TypePath)]
 pub struct Structure {
     pub color_a: Color,
     pub color_b: Color,
@@ -20,12 +24,16 @@ pub struct Structure {
     pub mesh: Handle<Mesh>,
     pub scale: f32,
     pub rotation: f32,
+    pub name_key: Option<String>,
+    pub description_key: Option<String>,
 }
 
 impl CodexSource for Structure {
-    const EXTENSION: &'static str = "structure.toml";
+    const EXTENSION: &'static str = "structure";
 }
 
+impl_localized_entry!(Structure, structure_codex);
+
 impl FromWithLoadContext<RawStructure> for Structure {
     fn from_with_load_context(raw: RawStructure, load_context: &mut LoadContext) -> Self {
         Self {
@@ -35,6 +43,8 @@ impl FromWithLoadContext<RawStructure> for Structure {
             mesh: load_context.load(raw.mesh),
             scale: raw.scale,
             rotation: raw.rotation,
+            name_key: raw.name_key,
+            description_key: raw.description_key,
         }
     }
 }
@@ -3,7 +3,7 @@ use crate::{actor::Actor, creature::Creature};
 use bevy::prelude::*;
 use expl_codex::Id;
 
-#[derive(Component, Reflect, Default, Deref)]
+#[derive(Component, Reflect, Copy, Clone, Default, Debug, Deref)]
 #[reflect(Component)]
 pub struct StructureId(pub Id<Structure>);
 
@@ -1,24 +1,25 @@
 use super::{asset::*, component::*, system::*};
 use crate::{
-    assets::AssetState,
+    assets::{AssetState, CodexAppExt},
     error,
     scene::{SceneSet, SceneState},
     turn::TurnState,
 };
 use bevy::prelude::*;
-use expl_codex::{Codex, CodexLoader, Id};
+use expl_codex::Id;
+use expl_map::MapAppExt;
 
 pub struct StructurePlugin;
 
 impl Plugin for StructurePlugin {
     fn build(&self, app: &mut App) {
-        app.init_asset::<Codex<Structure>>()
-            .init_asset_loader::<CodexLoader<RawStructure, Structure>>()
+        app.init_codex::<RawStructure, Structure>()
             .register_type::<Camp>()
             .register_type::<Id<Structure>>()
             .register_type::<Portal>()
             .register_type::<SafeHaven>()
             .register_type::<Spawner>()
+            .remember_presences::<StructureId>()
             .register_type::<StructureId>()
             .add_systems(Update, (update_camp_view_radius, update_portal_effect))
             .add_systems(
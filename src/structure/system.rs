@@ -1,15 +1,16 @@
 use super::{bundle::*, component::*, system_param::*};
 use crate::{
     actor::{ActorCodex, ActorParams, EnemyBundle, Members},
-    creature::{CreatureCodex, Health},
+    creature::{Creature, CreatureCodex, Health},
     floating_text::{FloatingTextAlignment, FloatingTextPrototype, FloatingTextSource},
+    locale::Localized,
     material::PortalMaterial,
     role::RoleCommandsExt,
     scene::save,
     ExplError,
 };
 use bevy::{color::palettes::css, pbr::NotShadowCaster, prelude::*};
-use expl_map::{Fog, MapCommandsExt, MapPresence, PresenceLayer, ViewRadius};
+use expl_map::{Fog, MapCommandsExt, MapPresence, OnMap, Presences, ViewRadius};
 
 #[allow(clippy::type_complexity)]
 pub fn fluff_structure(
@@ -39,25 +40,20 @@ pub fn charge_spawner(mut spawner_query: Query<&mut Spawner>) {
 
 pub fn spawn_enemy(
     mut commands: Commands,
-    mut spawner_query: Query<(&MapPresence, &mut Spawner)>,
+    mut spawner_query: Query<(&MapPresence, &OnMap, &mut Spawner)>,
     actor_codex: ActorCodex,
     creature_codex: CreatureCodex,
-    presence_query: Query<Entity, Without<Spawner>>,
-    map_query: Query<(Entity, &PresenceLayer)>,
+    localized_creature: Localized<Creature>,
+    others: Presences<Entity, Without<Spawner>>,
     mut creature_params: ActorParams,
 ) -> Result<(), ExplError> {
     let actor_codex = actor_codex.get()?;
     let creature_codex = creature_codex.get()?;
-    let (map_entity, presence_layer) = map_query.single()?;
-    for (presence, mut spawner) in &mut spawner_query {
-        if spawner.charge >= 3
-            && presence_query
-                .iter_many(presence_layer.presence(presence.position))
-                .next()
-                .is_none()
-        {
+    for (presence, &OnMap(map_entity), mut spawner) in &mut spawner_query {
+        if spawner.charge >= 3 && others.count(map_entity, presence.position) == 0 {
             spawner.charge -= 3;
             info!("Spawning enemy at {} from {:?}", presence.position, spawner);
+            let name = localized_creature.name(&spawner.creature)?;
             let (enemy_bundle, actor_role) = EnemyBundle::new(
                 presence.position,
                 creature_codex,
@@ -69,7 +65,7 @@ pub fn spawn_enemy(
                 .entity(map_entity)
                 .with_presence(presence.position, |location| {
                     location
-                        .spawn((Name::new("Enemy"), save::Save, enemy_bundle))
+                        .spawn((Name::new(name), save::Save, enemy_bundle))
                         .attach_role(actor_role);
                 });
         }
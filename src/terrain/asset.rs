@@ -1,5 +1,7 @@
+use super::component::SupplySource;
+use crate::{locale::impl_localized_entry, turn::Period};
 use bevy::{asset::LoadContext, prelude::*};
-use expl_codex::{CodexSource, FromWithLoadContext};
+use expl_codex::{AssetReference, CodexSource, FromWithLoadContext, Optional};
 use expl_hexagon::Hexagon;
 use serde::Deserialize;
 
@@ -8,14 +10,39 @@ pub struct HexAssets {
     pub mesh: Handle<Mesh>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Reflect)]
 pub enum TerrainDecoration {
     Water,
     Crystal,
     Tree,
 }
 
-#[derive(Clone, Debug, Default, TypePath, Deserialize)]
+/// Supplies found in a zone at each period of the day.
+#[derive(Clone, Debug, Default, PartialEq, Reflect, Deserialize)]
+#[serde(default)]
+pub struct YieldTable {
+    #[reflect(@Optional)]
+    pub morning: u32,
+    #[reflect(@Optional)]
+    pub day: u32,
+    #[reflect(@Optional)]
+    pub evening: u32,
+    #[reflect(@Optional)]
+    pub night: u32,
+}
+
+impl YieldTable {
+    pub fn get(&self, period: Period) -> u32 {
+        match period {
+            Period::Morning => self.morning,
+            Period::Day => self.day,
+            Period::Evening => self.evening,
+            Period::Night => self.night,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Reflect, Deserialize)]
 pub struct Terrain {
     pub symbol: char,
     pub allow_walking: bool,
@@ -26,35 +53,73 @@ pub struct Terrain {
     pub color_b: Color,
     pub color_c: Color,
     #[serde(default)]
+    #[reflect(@Optional)]
     pub decoration: Vec<TerrainDecoration>,
+    /// Action points it takes to move into the zone, one if not set.
+    #[serde(default)]
+    pub move_cost: Option<u16>,
+    /// Extra view radius for scouting from the zone.
+    #[serde(default)]
+    #[reflect(@Optional)]
+    pub elevation: u32,
+    #[serde(default)]
+    #[reflect(@Optional)]
+    pub forage: YieldTable,
+    #[serde(default)]
+    #[reflect(@Optional)]
+    pub hunt: YieldTable,
+    pub name_key: Option<String>,
+    pub description_key: Option<String>,
+}
+
+impl Terrain {
+    pub fn move_cost(&self) -> u16 {
+        self.move_cost.unwrap_or(1)
+    }
+
+    pub fn yield_table(&self, source: SupplySource) -> &YieldTable {
+        match source {
+            SupplySource::Forage => &self.forage,
+            SupplySource::Hunt => &self.hunt,
+        }
+    }
 }
 
 impl CodexSource for Terrain {
-    const EXTENSION: &'static str = "terrain.toml";
+    const EXTENSION: &'static str = "terrain";
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
-pub(super) struct RawDecoration {
+impl_localized_entry!(Terrain, terrain_codex);
+
+#[derive(Clone, Debug, Default, Deserialize, Reflect)]
+pub struct RawDecoration {
     color_a: Color,
     color_b: Color,
     color_c: Color,
+    #[reflect(@AssetReference)]
     mesh: String,
     scale: f32,
+    name_key: Option<String>,
+    description_key: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, TypePath)]
+#[derive(Clone, Debug, Default, PartialEq, TypePath)]
 pub struct Decoration {
     pub color_a: Color,
     pub color_b: Color,
     pub color_c: Color,
     pub mesh: Handle<Mesh>,
     pub scale: f32,
+    pub name_key: Option<String>,
+    pub description_key: Option<String>,
 }
 
 impl CodexSource for Decoration {
-    const EXTENSION: &'static str = "decoration.toml";
+    const EXTENSION: &'static str = "decoration";
 }
 
+impl_localized_entry!(Decoration, decoration_codex);
+
 impl FromWithLoadContext<RawDecoration> for Decoration {
     fn from_with_load_context(raw: RawDecoration, load_context: &mut LoadContext) -> Self {
         Self {
@@ -63,6 +128,8 @@ impl FromWithLoadContext<RawDecoration> for Decoration {
             color_c: raw.color_c,
             mesh: load_context.load(raw.mesh),
             scale: raw.scale,
+            name_key: raw.name_key,
+            description_key: raw.description_key,
         }
     }
 }
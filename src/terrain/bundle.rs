@@ -7,7 +7,7 @@ use crate::{
 use bevy::{pbr::NotShadowCaster, prelude::*};
 use expl_codex::{Codex, Id};
 use expl_hexgrid::Neighbours;
-use expl_map::{Fog, HexCoord, MapPosition};
+use expl_map::{FactionFog, Fog, HexCoord, MapPosition};
 use glam::Vec3Swizzles;
 
 pub type ZoneDecorationParams<'w> = (
@@ -147,6 +147,7 @@ impl Role for ZoneRole {
 pub struct ZoneBundle {
     terrain: TerrainId,
     fog: Fog,
+    faction_fog: FactionFog,
     position: MapPosition,
     zone_decorations: ZoneDecorations,
 }
@@ -0,0 +1,37 @@
+use super::{asset::Terrain, component::TerrainId, event::TerrainChanged};
+use bevy::{
+    ecs::system::{Command, EntityCommands},
+    prelude::*,
+};
+use expl_codex::Id;
+
+struct ChangeTerrain {
+    zone: Entity,
+    terrain: Id<Terrain>,
+}
+
+pub trait TerrainCommandsExt {
+    /// Change the terrain of the zone, its neighbours are refluffed to match the new terrain.
+    fn change_terrain(&mut self, terrain: Id<Terrain>) -> &mut Self;
+}
+
+impl TerrainCommandsExt for EntityCommands<'_> {
+    fn change_terrain(&mut self, terrain: Id<Terrain>) -> &mut Self {
+        let zone = self.id();
+        self.commands().queue(ChangeTerrain { zone, terrain });
+        self
+    }
+}
+
+impl Command for ChangeTerrain {
+    fn apply(self, world: &mut World) {
+        let Some(mut terrain_id) = world.get_mut::<TerrainId>(self.zone) else {
+            return;
+        };
+        if **terrain_id == self.terrain {
+            return;
+        }
+        *terrain_id = TerrainId(self.terrain);
+        world.send_event(TerrainChanged { zone: self.zone });
+    }
+}
@@ -29,6 +29,50 @@ impl CrystalDeposit {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupplySource {
+    Forage,
+    Hunt,
+}
+
+/// How often a zone has been foraged and hunted lately, every time halves the next yield.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Depletion {
+    pub forage: u8,
+    pub hunt: u8,
+}
+
+impl Depletion {
+    fn get_mut(&mut self, source: SupplySource) -> &mut u8 {
+        match source {
+            SupplySource::Forage => &mut self.forage,
+            SupplySource::Hunt => &mut self.hunt,
+        }
+    }
+
+    pub fn diminish(&self, source: SupplySource, amount: u32) -> u32 {
+        let depletion = match source {
+            SupplySource::Forage => self.forage,
+            SupplySource::Hunt => self.hunt,
+        };
+        amount.checked_shr(depletion.into()).unwrap_or(0)
+    }
+
+    pub fn deplete(mut self, source: SupplySource) -> Self {
+        let depletion = self.get_mut(source);
+        *depletion = depletion.saturating_add(1);
+        self
+    }
+
+    /// Recover a step of each, returns whether the zone has fully recovered.
+    pub fn recover(&mut self) -> bool {
+        self.forage = self.forage.saturating_sub(1);
+        self.hunt = self.hunt.saturating_sub(1);
+        *self == Self::default()
+    }
+}
+
 #[derive(Reflect, Default, Debug)]
 pub struct ZoneDecorationDetail {
     pub relative: Vec2,
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+/// Sent when the terrain of `zone` has been changed at runtime.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct TerrainChanged {
+    pub zone: Entity,
+}
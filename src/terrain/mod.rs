@@ -1,22 +1,29 @@
 mod asset;
 mod bundle;
+mod command;
 mod component;
+mod event;
 mod plugin;
 mod system;
 mod system_param;
 
 pub use asset::*;
 pub use bundle::*;
+pub use command::TerrainCommandsExt;
 pub use component::*;
+pub use event::*;
 pub use plugin::TerrainPlugin;
 pub use system_param::*;
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{system::adjust_presence_height, *};
+    use crate::test_fixture;
     use approx::assert_abs_diff_eq;
     use bevy::prelude::*;
-    use expl_hexgrid::Neighbours;
+    use expl_codex::{Codex, Id};
+    use expl_hexgrid::{HexCoord, Neighbours};
+    use expl_map::{MapCommandsExt, ZoneLayer};
 
     #[test]
     fn test_height() {
@@ -78,4 +85,124 @@ mod tests {
         assert_abs_diff_eq!(amp, 0.3, epsilon = 0.001);
         assert_abs_diff_eq!(base, 0.2, epsilon = 0.001);
     }
+
+    #[test]
+    fn test_change_terrain() {
+        let terrain_codex = Codex::from_iter(vec![
+            (
+                "forest",
+                Terrain {
+                    symbol: '%',
+                    allow_walking: true,
+                    ..default()
+                },
+            ),
+            (
+                "ocean",
+                Terrain {
+                    symbol: '~',
+                    ..default()
+                },
+            ),
+            (
+                "mountain",
+                Terrain {
+                    symbol: '^',
+                    allow_walking: true,
+                    ..default()
+                },
+            ),
+            (
+                "barren",
+                Terrain {
+                    symbol: '.',
+                    allow_walking: true,
+                    height_base: 0.5,
+                    ..default()
+                },
+            ),
+        ]);
+        let mut app = test_fixture::app::get(terrain_codex);
+        app.add_event::<TerrainChanged>();
+        let world = app.world_mut();
+        let (map, zone_layer) = world.query::<(Entity, &ZoneLayer)>().single(world).unwrap();
+        let zone = *zone_layer.get(HexCoord::ZERO).unwrap();
+        let neighbour = *zone_layer.get(HexCoord::new(1, 0)).unwrap();
+        let outer_terrain = OuterTerrain(Neighbours::new([Id::from_tag("forest"); 6]));
+        let zones: Vec<Entity> = world
+            .query_filtered::<Entity, With<TerrainId>>()
+            .iter(world)
+            .collect();
+        for zone in zones {
+            world.entity_mut(zone).insert(outer_terrain);
+        }
+        let presence = world
+            .spawn(Transform::from_translation(HexCoord::ZERO.into()))
+            .id();
+        let neighbour_presence = world
+            .spawn(Transform::from_translation(HexCoord::new(1, 0).into()))
+            .id();
+        world
+            .commands()
+            .entity(map)
+            .add_presence(presence, HexCoord::ZERO)
+            .add_presence(neighbour_presence, HexCoord::new(1, 0));
+        world.flush();
+        world.run_system_cached(adjust_presence_height).unwrap();
+
+        let terrain = **world.get::<TerrainId>(zone).unwrap();
+        world.commands().entity(zone).change_terrain(terrain);
+        world.flush();
+        assert!(world.resource::<Events<TerrainChanged>>().is_empty());
+
+        world
+            .commands()
+            .entity(zone)
+            .change_terrain(Id::from_tag("barren"));
+        world.flush();
+        assert_eq!(
+            **world.get::<TerrainId>(zone).unwrap(),
+            Id::from_tag("barren")
+        );
+        let changed: Vec<_> = world
+            .resource_mut::<Events<TerrainChanged>>()
+            .drain()
+            .map(|event| event.zone)
+            .collect();
+        assert_eq!(changed, vec![zone]);
+
+        // Stand in for `refluff_changed_terrain` which needs the render world to rebuild zones
+        world.entity_mut(zone).insert(outer_terrain);
+        world.entity_mut(neighbour).insert(outer_terrain);
+        world.run_system_cached(adjust_presence_height).unwrap();
+
+        let height = |entity| world.get::<Transform>(entity).unwrap().translation.y;
+        assert_abs_diff_eq!(height(presence), 0.5, epsilon = 0.001);
+        assert_abs_diff_eq!(height(neighbour_presence), 0.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn test_depletion() {
+        let terrain = Terrain {
+            forage: YieldTable {
+                day: 4,
+                ..default()
+            },
+            ..default()
+        };
+        let amount = terrain
+            .yield_table(SupplySource::Forage)
+            .get(crate::turn::Period::Day);
+        assert_eq!(amount, 4);
+
+        let mut depletion = Depletion::default()
+            .deplete(SupplySource::Forage)
+            .deplete(SupplySource::Forage);
+        assert_eq!(depletion.diminish(SupplySource::Forage, amount), 1);
+        assert_eq!(depletion.diminish(SupplySource::Hunt, amount), 4);
+
+        assert!(!depletion.recover());
+        assert_eq!(depletion.diminish(SupplySource::Forage, amount), 2);
+        assert!(depletion.recover());
+    }
 }
@@ -1,20 +1,21 @@
-use super::{asset::*, component::*, system::*};
+use super::{asset::*, component::*, event::*, system::*};
 use crate::{
+    assets::CodexAppExt,
     error,
     scene::{SceneSet, SceneState},
+    turn::{TurnSet, TurnState},
 };
 use bevy::prelude::*;
-use expl_codex::{Codex, CodexLoader, Id};
+use expl_codex::Id;
 
 pub struct TerrainPlugin;
 
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        app.init_asset::<Codex<Terrain>>()
-            .init_asset::<Codex<Decoration>>()
-            .init_asset_loader::<CodexLoader<Terrain>>()
-            .init_asset_loader::<CodexLoader<RawDecoration, Decoration>>()
+        app.init_codex::<Terrain, Terrain>()
+            .init_codex::<RawDecoration, Decoration>()
             .register_type::<CrystalDeposit>()
+            .register_type::<Depletion>()
             .register_type::<Height>()
             .register_type::<Height>()
             .register_type::<Option<ZoneDecorationDetail>>()
@@ -29,16 +30,26 @@ impl Plugin for TerrainPlugin {
             .register_type::<ZoneDecorationTree>()
             .register_type::<[f32; 6]>()
             .register_type::<[bool; 6]>()
+            .add_event::<TerrainChanged>()
             .add_systems(Startup, insert_hex_assets)
             .add_systems(
                 Update,
                 (
                     despawn_empty_crystal_deposit,
+                    refluff_changed_terrain
+                        .map(error::warn)
+                        .run_if(on_event::<TerrainChanged>)
+                        .before(hide_decorations_behind_camp),
+                    adjust_presence_height.after(refluff_changed_terrain),
                     hide_decorations_behind_camp,
                     show_decorations_behind_camp,
                     update_outer_visible,
                 ),
             )
+            .add_systems(
+                OnEnter(TurnState::Player),
+                recover_depletion.in_set(TurnSet::Effects),
+            )
             .add_systems(
                 OnEnter(SceneState::Active),
                 (
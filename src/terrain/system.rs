@@ -1,9 +1,23 @@
-use super::{asset::*, bundle::*, component::*, system_param::*};
+use super::{asset::*, bundle::*, component::*, event::*, system_param::*};
 use crate::{role::RoleCommandsExt, structure::Camp, ExplError};
 use bevy::prelude::*;
-use expl_codex::Id;
-use expl_hexgrid::{Grid, Neighbours};
-use expl_map::{Fog, MapEvent, MapLayout, MapPosition, MapPresence, PresenceLayer, ZoneLayer};
+use expl_codex::{Codex, Id};
+use expl_hexgrid::{Grid, HexCoord, Neighbours};
+use expl_map::{
+    Fog, MapEvent, MapLayout, MapPosition, MapPresence, OnMap, PresenceLayer, ZoneLayer,
+};
+use std::collections::HashSet;
+
+pub fn recover_depletion(
+    mut commands: Commands,
+    mut depletion_query: Query<(Entity, &mut Depletion)>,
+) {
+    for (zone, mut depletion) in &mut depletion_query {
+        if depletion.recover() {
+            commands.entity(zone).remove::<Depletion>();
+        }
+    }
+}
 
 pub fn despawn_empty_crystal_deposit(
     mut commands: Commands,
@@ -132,6 +146,65 @@ pub fn fluff_zone(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+fn spawn_decorations(
+    parent: &mut ChildSpawnerCommands,
+    terrain: &Terrain,
+    zone_decorations: &ZoneDecorations,
+    crystal_deposit: Option<&CrystalDeposit>,
+    height: &Height,
+    fog: &Fog,
+    position: HexCoord,
+    zone_decoration_params: &mut ZoneDecorationParams,
+    water_params: &mut WaterParams,
+    decoration_codex: &Codex<Decoration>,
+) {
+    for decoration in &terrain.decoration {
+        match decoration {
+            TerrainDecoration::Crystal => {
+                let Some(detail) = &zone_decorations.crystal_detail else {
+                    continue;
+                };
+                if crystal_deposit.is_some_and(|deposit| deposit.amount > 0) {
+                    parent.spawn((
+                        Name::new("Crystal"),
+                        ZoneDecorationBundle::new(
+                            ZoneDecorationCrystals,
+                            Id::from_tag("crystal"),
+                            zone_decoration_params,
+                            decoration_codex,
+                            height,
+                            fog,
+                            position,
+                            detail,
+                        ),
+                    ));
+                }
+            }
+            TerrainDecoration::Tree => {
+                for detail in &zone_decorations.tree_details {
+                    parent.spawn((
+                        Name::new("Tree"),
+                        ZoneDecorationBundle::new(
+                            ZoneDecorationTree,
+                            Id::from_tag("tree"),
+                            zone_decoration_params,
+                            decoration_codex,
+                            height,
+                            fog,
+                            position,
+                            detail,
+                        ),
+                    ));
+                }
+            }
+            TerrainDecoration::Water => {
+                parent.spawn((Name::new("Water"), WaterBundle::new(water_params)));
+            }
+        }
+    }
+}
+
 pub fn decorate_zone(
     mut commands: Commands,
     zone_query: Query<(
@@ -141,6 +214,7 @@ pub fn decorate_zone(
         &Fog,
         &OuterTerrain,
         &ZoneDecorations,
+        Option<&CrystalDeposit>,
     )>,
     mut zone_decoration_params: ZoneDecorationParams,
     mut water_params: WaterParams,
@@ -149,56 +223,157 @@ pub fn decorate_zone(
 ) -> Result<(), ExplError> {
     let terrain_codex = terrain_codex.get()?;
     let decoration_codex = decoration_codex.get()?;
-    for (entity, terrain_id, position, fog, outer_terrain, zone_decorations) in &zone_query {
-        let terrain = &terrain_codex[terrain_id];
+    for (entity, terrain_id, position, fog, outer_terrain, zone_decorations, crystal_deposit) in
+        &zone_query
+    {
         let height = Height::new(terrain_codex, **terrain_id, outer_terrain);
         commands.entity(entity).with_children(|parent| {
-            for decoration in &terrain.decoration {
-                match decoration {
-                    TerrainDecoration::Crystal => {
-                        if let Some(detail) = &zone_decorations.crystal_detail {
-                            parent.spawn((
-                                Name::new("Crystal"),
-                                ZoneDecorationBundle::new(
-                                    ZoneDecorationCrystals,
-                                    Id::from_tag("crystal"),
-                                    &mut zone_decoration_params,
-                                    decoration_codex,
-                                    &height,
-                                    fog,
-                                    **position,
-                                    detail,
-                                ),
-                            ));
-                        }
-                    }
-                    TerrainDecoration::Tree => {
-                        for detail in &zone_decorations.tree_details {
-                            parent.spawn((
-                                Name::new("Tree"),
-                                ZoneDecorationBundle::new(
-                                    ZoneDecorationTree,
-                                    Id::from_tag("tree"),
-                                    &mut zone_decoration_params,
-                                    decoration_codex,
-                                    &height,
-                                    fog,
-                                    **position,
-                                    detail,
-                                ),
-                            ));
-                        }
-                    }
-                    TerrainDecoration::Water => {
-                        parent.spawn((Name::new("Water"), WaterBundle::new(&mut water_params)));
-                    }
-                }
-            }
+            spawn_decorations(
+                parent,
+                &terrain_codex[terrain_id],
+                zone_decorations,
+                crystal_deposit,
+                &height,
+                fog,
+                **position,
+                &mut zone_decoration_params,
+                &mut water_params,
+                decoration_codex,
+            );
         });
     }
     Ok(())
 }
 
+/// Rebuild the zones whose terrain has changed along with their neighbours, as the outer terrain,
+/// and therefore the height at the edges, of the neighbours depends on it.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn refluff_changed_terrain(
+    mut commands: Commands,
+    mut terrain_changed_events: EventReader<TerrainChanged>,
+    mut zone_params: ZoneParams,
+    mut zone_decoration_params: ZoneDecorationParams,
+    mut water_params: WaterParams,
+    terrain_codex: TerrainCodex,
+    decoration_codex: DecorationCodex,
+    map_query: Query<(&ZoneLayer, &PresenceLayer)>,
+    zone_query: Query<(
+        &TerrainId,
+        &MapPosition,
+        &OnMap,
+        &Fog,
+        &OuterVisible,
+        &ZoneDecorations,
+        Option<&CrystalDeposit>,
+        Option<&Children>,
+    )>,
+    decoration_query: Query<
+        Entity,
+        Or<(
+            With<ZoneDecorationCrystals>,
+            With<ZoneDecorationTree>,
+            With<Water>,
+        )>,
+    >,
+    mut camp_query: Query<&mut MapPresence, With<Camp>>,
+) -> Result<(), ExplError> {
+    let terrain_codex = terrain_codex.get()?;
+    let decoration_codex = decoration_codex.get()?;
+
+    let mut affected = HashSet::new();
+    for event in terrain_changed_events.read() {
+        let Ok((_, &MapPosition(position), &OnMap(map), ..)) = zone_query.get(event.zone) else {
+            continue;
+        };
+        affected.insert((map, position));
+        affected.extend(position.neighbours().map(|coord| (map, coord)));
+    }
+
+    let void = Id::from_tag("void");
+    for (map, position) in affected {
+        let Ok((zone_layer, presence_layer)) = map_query.get(map) else {
+            continue;
+        };
+        let Some(&zone) = zone_layer.get(position) else {
+            continue;
+        };
+        let Ok((
+            terrain_id,
+            map_position,
+            _,
+            fog,
+            outer_visible,
+            zone_decorations,
+            deposit,
+            children,
+        )) = zone_query.get(zone)
+        else {
+            continue;
+        };
+
+        let outer_terrain = Neighbours::from_fn_around(position, |coord| {
+            zone_layer
+                .get(coord)
+                .and_then(|&e| zone_query.get(e).ok())
+                .map_or(void, |(terrain_id, ..)| **terrain_id)
+        });
+        commands.entity(zone).attach_role(ZoneRole::new(
+            &mut zone_params,
+            map_position,
+            terrain_id,
+            fog,
+            *outer_visible,
+            outer_terrain,
+        ));
+
+        for decoration in decoration_query.iter_many(children.map_or(&[][..], |c| &c[..])) {
+            commands.entity(decoration).despawn();
+        }
+        let height = Height::new(terrain_codex, **terrain_id, &outer_terrain);
+        commands.entity(zone).with_children(|parent| {
+            spawn_decorations(
+                parent,
+                &terrain_codex[terrain_id],
+                zone_decorations,
+                deposit,
+                &height,
+                fog,
+                position,
+                &mut zone_decoration_params,
+                &mut water_params,
+                decoration_codex,
+            );
+        });
+
+        // Let `hide_decorations_behind_camp` clear the new trees around the camp
+        let mut camp_iter = camp_query.iter_many_mut(presence_layer.presence(position));
+        while let Some(mut camp_presence) = camp_iter.fetch_next() {
+            camp_presence.set_changed();
+        }
+    }
+    Ok(())
+}
+
+/// Move the presences in zones with new outer terrain to the height of the rebuilt zone.
+///
+/// Runs after `refluff_changed_terrain` as the height is read from the `OuterTerrain` it inserts.
+pub fn adjust_presence_height(
+    map_query: Query<&PresenceLayer>,
+    zone_query: Query<(&MapPosition, &OnMap), Changed<OuterTerrain>>,
+    mut presence_query: Query<&mut Transform, With<MapPresence>>,
+    height_query: HeightQuery,
+) {
+    for (&MapPosition(position), &OnMap(map)) in &zone_query {
+        let Ok(presence_layer) = map_query.get(map) else {
+            continue;
+        };
+        let mut presence_iter = presence_query.iter_many_mut(presence_layer.presence(position));
+        while let Some(mut transform) = presence_iter.fetch_next() {
+            transform.translation = height_query.adjust(transform.translation);
+        }
+    }
+}
+
 pub fn update_outer_visible(
     map_query: Query<&ZoneLayer>,
     changed_zone_query: Query<(Entity, &Fog, &MapPosition), Changed<Fog>>,
@@ -4,34 +4,41 @@ use crate::{
 };
 use bevy::prelude::*;
 use expl_codex::Codex;
-use expl_hexgrid::layout::SquareGridLayout;
-use expl_map::ZoneLayer;
+use expl_hexgrid::{layout::SquareGridLayout, GridLayout};
+use expl_map::{MapPosition, OnMap, PresenceLayer, ZoneLayer};
 use rstest::*;
 
+/// Spawn a map of 3x3 zones without terrain.
+pub fn spawn_test_map(world: &mut World) -> Entity {
+    let layout = SquareGridLayout {
+        width: 3,
+        height: 3,
+    };
+    let map = world.spawn(PresenceLayer::new(layout)).id();
+    let zones = layout
+        .iter()
+        .map(|position| world.spawn((MapPosition(position), OnMap(map))).id())
+        .collect();
+    world.entity_mut(map).insert(ZoneLayer::new(layout, zones));
+    map
+}
+
 pub fn spawn_game_map(app: &mut App) -> Entity {
-    let tiles = app
-        .world_mut()
-        .spawn_batch(vec![
-            TerrainId::from_tag("forest"),
-            TerrainId::from_tag("forest"),
-            TerrainId::from_tag("forest"),
-            TerrainId::from_tag("ocean"),
-            TerrainId::from_tag("ocean"),
-            TerrainId::from_tag("forest"),
-            TerrainId::from_tag("mountain"),
-            TerrainId::from_tag("mountain"),
-            TerrainId::from_tag("mountain"),
-        ])
+    let world = app.world_mut();
+    let map = spawn_test_map(world);
+    let zone_layer = world.get::<ZoneLayer>(map).unwrap();
+    let zones: Vec<Entity> = zone_layer
+        .layout()
+        .iter()
+        .map(|position| *zone_layer.get(position).unwrap())
         .collect();
-    app.world_mut()
-        .spawn(ZoneLayer::new(
-            SquareGridLayout {
-                width: 3,
-                height: 3,
-            },
-            tiles,
-        ))
-        .id()
+    for (zone, tag) in zones.into_iter().zip([
+        "forest", "forest", "forest", "ocean", "ocean", "forest", "mountain", "mountain",
+        "mountain",
+    ]) {
+        world.entity_mut(zone).insert(TerrainId::from_tag(tag));
+    }
+    map
 }
 
 #[fixture]
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use expl_map::MapTurn;
+use serde::{Deserialize, Serialize};
 
 #[derive(Resource, Reflect, Copy, Clone, Default, Debug, Deref, DerefMut)]
 #[reflect(Resource)]
@@ -29,7 +31,7 @@ impl From<Turn> for Period {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash, States, Default)]
 pub enum TurnState {
     #[default]
     System,
@@ -59,7 +61,8 @@ impl Plugin for TurnPlugin {
                 (update_turn_counter, apply_period)
                     .chain()
                     .in_set(TurnSet::Setup),
-            );
+            )
+            .add_systems(Update, sync_map_turn.run_if(resource_changed::<Turn>));
     }
 }
 
@@ -75,6 +78,10 @@ fn update_turn_counter(mut turn: ResMut<Turn>) {
     **turn += 1;
 }
 
+fn sync_map_turn(turn: Res<Turn>, mut map_turn: ResMut<MapTurn>) {
+    map_turn.set_if_neq(MapTurn(turn.number));
+}
+
 fn apply_period(turn: Res<Turn>, mut period_state: ResMut<Period>) {
     *period_state = Period::from(*turn);
 }